@@ -0,0 +1,201 @@
+// 原子写入
+// 先写入同目录下的临时文件并 fsync，再重命名覆盖目标文件，
+// 保证崩溃或磁盘写满时不会留下被截断的文章或配置文件
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+// 保留的备份数量：最新的是 .foo.md.bak，更早的依次为 .foo.md.bak.1、.foo.md.bak.2
+pub const BACKUP_COUNT: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteOptions {
+    // 覆盖前把旧内容保存到隐藏的 .bak 文件，轮换保留最近 BACKUP_COUNT 份
+    pub backup: bool,
+}
+
+// 备份文件路径：foo.md -> .foo.md.bak，index 大于 0 时为 .foo.md.bak.<index>
+// 使用隐藏文件名，避免被 Hexo 当作资源文件处理，也不会出现在文章列表中
+pub fn backup_path(path: &Path, index: usize) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy();
+    if index == 0 {
+        Some(path.with_file_name(format!(".{}.bak", name)))
+    } else {
+        Some(path.with_file_name(format!(".{}.bak.{}", name, index)))
+    }
+}
+
+// 轮换备份后写入最新的备份；旧内容与最新备份相同时不轮换，避免连续保存把更早的备份挤掉
+fn write_backup(path: &Path, previous: &[u8]) -> io::Result<()> {
+    let Some(latest) = backup_path(path, 0) else {
+        return Ok(());
+    };
+    if fs::read(&latest).is_ok_and(|bytes| bytes == previous) {
+        return Ok(());
+    }
+    for index in (1..BACKUP_COUNT).rev() {
+        if let (Some(from), Some(to)) = (backup_path(path, index - 1), backup_path(path, index)) {
+            match fs::rename(&from, &to) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+    }
+    write_atomic(&latest, previous, WriteOptions::default())
+}
+
+fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "无效的文件路径"))?
+        .to_string_lossy();
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    Ok(path.with_file_name(format!(
+        ".{}.{}-{}-{}.tmp",
+        name,
+        std::process::id(),
+        nanos,
+        counter
+    )))
+}
+
+// 同步目录项，确保重命名本身落盘（仅 Unix 支持打开目录）
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+fn write_temp(temp: &Path, contents: &[u8], permissions: Option<fs::Permissions>) -> io::Result<()> {
+    let mut file = OpenOptions::new().write(true).create_new(true).open(temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+
+    // 保留原文件的权限
+    if let Some(permissions) = permissions {
+        fs::set_permissions(temp, permissions)?;
+    }
+    Ok(())
+}
+
+// 原子地写入文件内容
+pub fn write_atomic(path: &Path, contents: &[u8], options: WriteOptions) -> io::Result<()> {
+    write_atomic_checked(path, contents, options, || Ok::<(), io::Error>(()))
+}
+
+// 与 write_atomic 相同，但在重命名覆盖目标文件之前调用 check，返回错误时放弃写入
+// （用于确认磁盘内容仍是调用方读取时的版本，把检查和覆盖之间的间隔缩到最短）
+pub fn write_atomic_checked<E: From<io::Error>>(
+    path: &Path,
+    contents: &[u8],
+    options: WriteOptions,
+    check: impl FnOnce() -> Result<(), E>,
+) -> Result<(), E> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+
+    let existing = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "目标路径是一个目录").into());
+        }
+        Ok(metadata) => Some(metadata),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let temp = temp_path(path)?;
+    if let Err(e) = write_temp(&temp, contents, existing.as_ref().map(|m| m.permissions())) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    if let Err(e) = check() {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    if options.backup && existing.is_some() {
+        if let Err(e) = fs::read(path).and_then(|previous| write_backup(path, &previous)) {
+            let _ = fs::remove_file(&temp);
+            return Err(e.into());
+        }
+    }
+
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+
+    Ok(sync_dir(&dir)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BACKUP: WriteOptions = WriteOptions { backup: true };
+
+    #[test]
+    fn rotates_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("_config.yml");
+        for version in ["v1", "v2", "v3", "v4", "v5"] {
+            write_atomic(&path, version.as_bytes(), BACKUP).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "v5");
+        let backups: Vec<String> = (0..BACKUP_COUNT)
+            .map(|index| fs::read_to_string(backup_path(&path, index).unwrap()).unwrap())
+            .collect();
+        assert_eq!(backups, ["v4", "v3", "v2"]);
+        assert!(!backup_path(&path, BACKUP_COUNT).unwrap().exists());
+    }
+
+    #[test]
+    fn unchanged_content_does_not_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        write_atomic(&path, b"good", BACKUP).unwrap();
+        write_atomic(&path, b"bad", BACKUP).unwrap();
+        write_atomic(&path, b"bad", BACKUP).unwrap();
+        write_atomic(&path, b"bad", BACKUP).unwrap();
+        assert_eq!(fs::read_to_string(backup_path(&path, 0).unwrap()).unwrap(), "bad");
+        assert_eq!(fs::read_to_string(backup_path(&path, 1).unwrap()).unwrap(), "good");
+    }
+
+    #[test]
+    fn no_temp_files_left() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        write_atomic(&path, b"a", WriteOptions::default()).unwrap();
+        write_atomic(&path, b"b", WriteOptions::default()).unwrap();
+        let names: Vec<_> = fs::read_dir(dir.path()).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(names, ["post.md"]);
+    }
+
+    #[test]
+    fn failed_check_keeps_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        fs::write(&path, "original").unwrap();
+        let result = write_atomic_checked(&path, b"updated", BACKUP, || Err(io::Error::other("changed")));
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,676 @@
+// 文章元数据存储
+// 应用数据目录下的 SQLite 数据库（content.db）保存各项目已索引的文章、标签、分类和全文索引，
+// 启动后不必重新解析所有文章：刷新时只重新解析修改时间或大小变化过的文件
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rayon::prelude::*;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::{self, CommandError};
+use crate::post_index::{self, PostSummary};
+use crate::post_move::is_markdown;
+use crate::scope::PathScope;
+use crate::search::{self, SearchDocument};
+
+// 数据库结构版本，修改表结构时递增并在 migrate 中处理
+const SCHEMA_VERSION: i32 = 5;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const MAX_PAGE_SIZE: usize = 500;
+// post_categories.path 中分隔各级分类的字符。分类名本身可能含有 /，因此使用不会出现在名称中的单元分隔符
+const CATEGORY_SEPARATOR: &str = "\u{1f}";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS posts (
+    id INTEGER PRIMARY KEY,
+    project TEXT NOT NULL,
+    relative_path TEXT NOT NULL,
+    slug TEXT,
+    title TEXT,
+    date TEXT,
+    updated TEXT,
+    -- 规范化为 YYYY-MM-DD HH:MM:SS，用于排序和按日期筛选
+    date_sort TEXT,
+    updated_sort TEXT,
+    draft INTEGER NOT NULL,
+    published INTEGER NOT NULL,
+    word_count INTEGER NOT NULL,
+    has_excerpt INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    modified_ns INTEGER NOT NULL,
+    error TEXT,
+    UNIQUE (project, relative_path)
+);
+CREATE TABLE IF NOT EXISTS post_tags (
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL
+);
+-- 每条分类路径的所有前缀都会写入，[技术, Rust] 对应 \"技术\" 和 \"技术␟Rust\" 两行（␟ 即 CATEGORY_SEPARATOR），
+-- 按分类筛选时自然包含子分类；depth 为前缀的层数，leaf 表示是否为完整路径
+CREATE TABLE IF NOT EXISTS post_categories (
+    post_id INTEGER NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    path_index INTEGER NOT NULL,
+    depth INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    leaf INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_posts_project ON posts(project);
+CREATE INDEX IF NOT EXISTS idx_post_tags_post ON post_tags(post_id);
+CREATE INDEX IF NOT EXISTS idx_post_tags_name ON post_tags(name);
+CREATE INDEX IF NOT EXISTS idx_post_categories_post ON post_categories(post_id);
+CREATE INDEX IF NOT EXISTS idx_post_categories_path ON post_categories(path);
+";
+
+// 版本 2：全文搜索。post_search 中保存 search::index_text 切分后的词元，post_content 保存原文用于生成摘要
+const SEARCH_SCHEMA: &str = "
+CREATE VIRTUAL TABLE IF NOT EXISTS post_search USING fts5(title, front_matter, body, tokenize = 'unicode61');
+CREATE TABLE IF NOT EXISTS post_content (
+    post_id INTEGER PRIMARY KEY REFERENCES posts(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL
+);
+CREATE TRIGGER IF NOT EXISTS posts_delete_search AFTER DELETE ON posts BEGIN
+    DELETE FROM post_search WHERE rowid = old.id;
+END;
+";
+
+// 搜索结果的一行：文章、bm25 分数和用于生成摘要的原文
+pub type SearchRow = (PostSummary, f64, SearchDocument);
+
+pub struct ContentStore {
+    conn: Mutex<Connection>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostQuery {
+    pub tag: Option<String>,
+    // 分类路径（从顶级分类开始的各级名称），包含子分类中的文章
+    pub category: Option<Vec<String>>,
+    // 日期范围（包含两端），可以只写到日或月，例如 "2024-01" 或 "2024-01-31"
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub draft: Option<bool>,
+    // 匹配标题、路径和标签（不区分大小写）
+    pub text: Option<String>,
+    // "date" | "updated" | "title" | "wordCount" | "modifiedTime" | "path"，默认 "date"
+    pub sort_by: Option<String>,
+    // "asc" | "desc"，默认 "desc"
+    pub sort_order: Option<String>,
+    // 从 1 开始
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostPage {
+    pub posts: Vec<PostSummary>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+pub struct DatedPost {
+    // 规范化后的 date / updated（YYYY-MM-DD HH:MM:SS），无法识别时为 None
+    pub date: Option<String>,
+    pub updated: Option<String>,
+    pub word_count: usize,
+    pub tags: Vec<String>,
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshStats {
+    // 本次重新解析的文章数
+    pub parsed: usize,
+    // 未变化、直接使用已存数据的文章数
+    pub cached: usize,
+    pub removed: usize,
+}
+
+fn modified_ns(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        conn.execute_batch(SCHEMA)?;
+    }
+    if version < 2 {
+        conn.execute_batch(SEARCH_SCHEMA)?;
+    }
+    if (1..SCHEMA_VERSION).contains(&version) {
+        // 版本 2 增加了全文索引，版本 3 修改了字数统计规则，版本 4 修改了分类路径的分隔符，
+        // 版本 5 修改了中日韩文字的切分，已有的文章清空后在下次刷新时重新解析
+        conn.execute("DELETE FROM posts", [])?;
+    }
+    if version < SCHEMA_VERSION {
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    Ok(())
+}
+
+impl ContentStore {
+    pub fn open(path: &Path) -> Result<Self, CommandError> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        migrate(&conn)?;
+        Ok(ContentStore { conn: Mutex::new(conn) })
+    }
+
+    // 数据库只是缓存：打开或迁移失败（文件损坏、被其他程序锁定等）时删除后重建，
+    // 仍然无法打开时退回内存数据库，不阻止程序启动
+    pub fn open_or_recreate(path: &Path) -> Result<Self, CommandError> {
+        let e = match Self::open(path) {
+            Ok(store) => return Ok(store),
+            Err(e) => e,
+        };
+        log::warn!("打开文章索引数据库失败，删除后重建 {}: {}", path.display(), e);
+        for suffix in ["", "-wal", "-shm"] {
+            let mut file = path.as_os_str().to_owned();
+            file.push(suffix);
+            if let Err(e) = fs::remove_file(&file) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("删除 {} 失败: {}", Path::new(&file).display(), e);
+                }
+            }
+        }
+        Self::open(path).or_else(|e| {
+            log::warn!("重建文章索引数据库失败，改用内存数据库: {}", e);
+            Self::open(Path::new(":memory:"))
+        })
+    }
+
+    // 按修改时间刷新项目的文章索引
+    pub fn refresh(&self, project_root: &Path) -> Result<RefreshStats, CommandError> {
+        let project = project_root.to_string_lossy().to_string();
+        let files: Vec<(PathBuf, String, u64, SystemTime)> = post_index::post_files(project_root)
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok()?;
+                let relative = path.strip_prefix(project_root).ok()?.to_string_lossy().replace('\\', "/");
+                Some((path, relative, metadata.len(), metadata.modified().unwrap_or(UNIX_EPOCH)))
+            })
+            .collect();
+
+        let stored: HashMap<String, (i64, i64)> = {
+            let conn = self.conn.lock().unwrap();
+            let mut statement = conn.prepare("SELECT relative_path, size, modified_ns FROM posts WHERE project = ?1")?;
+            let rows = statement.query_map([&project], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?))))?;
+            rows.collect::<rusqlite::Result<_>>()?
+        };
+
+        // 解析在锁外并行进行
+        let changed: Vec<(PostSummary, SearchDocument)> = files
+            .par_iter()
+            .filter(|(_, relative, size, modified)| stored.get(relative) != Some(&(*size as i64, modified_ns(*modified))))
+            .map(|(path, _, size, modified)| post_index::summarize(project_root, path, *size, *modified))
+            .collect();
+        let current: HashMap<&str, SystemTime> = files
+            .iter()
+            .map(|(_, relative, _, modified)| (relative.as_str(), *modified))
+            .collect();
+        let removed: Vec<&String> = stored.keys().filter(|relative| !current.contains_key(relative.as_str())).collect();
+
+        let stats = RefreshStats {
+            parsed: changed.len(),
+            cached: files.len() - changed.len(),
+            removed: removed.len(),
+        };
+        if changed.is_empty() && removed.is_empty() {
+            return Ok(stats);
+        }
+
+        let mut conn = self.conn.lock().unwrap();
+        let transaction = conn.transaction()?;
+        for relative in &removed {
+            transaction.execute(
+                "DELETE FROM posts WHERE project = ?1 AND relative_path = ?2",
+                params![project, relative],
+            )?;
+        }
+        for (summary, document) in &changed {
+            let modified = current.get(summary.relative_path.as_str()).copied().unwrap_or(UNIX_EPOCH);
+            upsert_post(&transaction, &project, summary, document, modified_ns(modified))?;
+        }
+        transaction.commit()?;
+        Ok(stats)
+    }
+
+    // 重新索引单篇文章（保存、移动文章后调用），文件不存在时移除；不是 source/_posts 或 source/_drafts 下的 markdown 时忽略
+    pub fn index_file(&self, project_root: &Path, path: &Path) -> Result<(), CommandError> {
+        let Ok(relative) = path.strip_prefix(project_root) else {
+            return Ok(());
+        };
+        let source = Path::new("source");
+        if !(relative.starts_with(source.join("_posts")) || relative.starts_with(source.join("_drafts"))) || !is_markdown(path) {
+            return Ok(());
+        }
+        // 文件已被移走或删除时移除索引
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let conn = self.conn.lock().unwrap();
+                conn.execute(
+                    "DELETE FROM posts WHERE project = ?1 AND relative_path = ?2",
+                    params![project_root.to_string_lossy(), relative.to_string_lossy().replace('\\', "/")],
+                )?;
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+        let (summary, document) = post_index::summarize(project_root, path, metadata.len(), modified);
+
+        let conn = self.conn.lock().unwrap();
+        upsert_post(&conn, &project_root.to_string_lossy(), &summary, &document, modified_ns(modified))?;
+        Ok(())
+    }
+
+    // 全文搜索，match_query 为 FTS5 查询语句；返回当前页的结果和总数
+    pub fn search(
+        &self,
+        project_root: &Path,
+        display_root: &Path,
+        match_query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<SearchRow>, usize), CommandError> {
+        let project = project_root.to_string_lossy().to_string();
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM post_search JOIN posts p ON p.id = post_search.rowid \
+             WHERE post_search MATCH ?1 AND p.project = ?2",
+            params![match_query, project],
+            |row| row.get(0),
+        )?;
+
+        let mut statement = conn.prepare(
+            "SELECT p.id, p.relative_path, p.slug, p.title, p.date, p.updated, p.draft, p.published, \
+             p.word_count, p.has_excerpt, p.size, p.modified_ns, p.error, \
+             bm25(post_search, 10.0, 3.0, 1.0) AS score, c.title, c.body \
+             FROM post_search JOIN posts p ON p.id = post_search.rowid LEFT JOIN post_content c ON c.post_id = p.id \
+             WHERE post_search MATCH ?1 AND p.project = ?2 ORDER BY score LIMIT ?3 OFFSET ?4",
+        )?;
+        let rows = statement.query_map(params![match_query, project, limit as i64, offset as i64], |row| {
+            let (id, summary) = row_to_summary(row, display_root)?;
+            let document = SearchDocument {
+                title: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+                front_matter: String::new(),
+                body: row.get::<_, Option<String>>(15)?.unwrap_or_default(),
+            };
+            Ok((id, summary, row.get::<_, f64>(13)?, document))
+        })?;
+
+        let mut results = Vec::new();
+        for row in rows {
+            let (id, mut summary, score, document) = row?;
+            load_taxonomies(&conn, id, &mut summary)?;
+            results.push((summary, score, document));
+        }
+        Ok((results, total as usize))
+    }
+
+    // 读取项目的文章；display_root 为前端使用的项目路径，用于生成结果中的 path
+    pub fn query(&self, project_root: &Path, display_root: &Path, query: &PostQuery) -> Result<PostPage, CommandError> {
+        let mut values: Vec<String> = Vec::new();
+        // 绑定一个参数，返回其编号占位符
+        let mut bind = |value: String| {
+            values.push(value);
+            format!("?{}", values.len())
+        };
+
+        let mut conditions = vec![format!("p.project = {}", bind(project_root.to_string_lossy().to_string()))];
+        if let Some(tag) = &query.tag {
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM post_tags t WHERE t.post_id = p.id AND t.name = {})",
+                bind(tag.clone())
+            ));
+        }
+        if let Some(category) = &query.category {
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM post_categories c WHERE c.post_id = p.id AND c.path = {})",
+                bind(join_category(category))
+            ));
+        }
+        if let Some(date_from) = &query.date_from {
+            conditions.push(format!("p.date_sort >= {}", bind(date_from.clone())));
+        }
+        if let Some(date_to) = &query.date_to {
+            // 只比较与 date_to 等长的前缀，"2024-01" 包含整个一月
+            conditions.push(format!("substr(p.date_sort, 1, length({0})) <= {0}", bind(date_to.clone())));
+        }
+        if let Some(draft) = query.draft {
+            conditions.push(format!("p.draft = {}", draft as i32));
+        }
+        if let Some(text) = query.text.as_deref().map(str::trim).filter(|text| !text.is_empty()) {
+            let pattern = format!("%{}%", text.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+            conditions.push(format!(
+                "(p.title LIKE {0} ESCAPE '\\' OR p.relative_path LIKE {0} ESCAPE '\\' \
+                 OR EXISTS (SELECT 1 FROM post_tags t WHERE t.post_id = p.id AND t.name LIKE {0} ESCAPE '\\'))",
+                bind(pattern)
+            ));
+        }
+        let where_clause = conditions.join(" AND ");
+
+        let sort_column = match query.sort_by.as_deref().unwrap_or("date") {
+            "date" => "p.date_sort",
+            "updated" => "COALESCE(p.updated_sort, p.date_sort)",
+            "title" => "p.title COLLATE NOCASE",
+            "wordCount" => "p.word_count",
+            "modifiedTime" => "p.modified_ns",
+            "path" => "p.relative_path",
+            other => {
+                return Err(CommandError::new(error::INVALID_ARGUMENT, format!("不支持的排序字段: {}", other)));
+            }
+        };
+        let sort_order = match query.sort_order.as_deref().unwrap_or("desc") {
+            "asc" => "ASC",
+            "desc" => "DESC",
+            other => {
+                return Err(CommandError::new(error::INVALID_ARGUMENT, format!("不支持的排序方向: {}", other)));
+            }
+        };
+
+        let page_size = query.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1);
+        let page = query.page.unwrap_or(1).max(1);
+
+        let conn = self.conn.lock().unwrap();
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM posts p WHERE {}", where_clause),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+
+        let sql = format!(
+            "SELECT p.id, p.relative_path, p.slug, p.title, p.date, p.updated, p.draft, p.published, \
+             p.word_count, p.has_excerpt, p.size, p.modified_ns, p.error \
+             FROM posts p WHERE {} ORDER BY {} IS NULL, {} {}, p.relative_path LIMIT {} OFFSET {}",
+            where_clause,
+            sort_column,
+            sort_column,
+            sort_order,
+            page_size.min(i64::MAX as usize),
+            (page - 1).saturating_mul(page_size)
+        );
+        let mut statement = conn.prepare(&sql)?;
+        let rows = statement.query_map(params_from_iter(values.iter()), |row| row_to_summary(row, display_root))?;
+        let mut posts = Vec::new();
+        for row in rows {
+            let (id, mut summary) = row?;
+            load_taxonomies(&conn, id, &mut summary)?;
+            posts.push(summary);
+        }
+
+        Ok(PostPage {
+            posts,
+            total: total as usize,
+            page,
+            page_size,
+        })
+    }
+
+    // 各分类路径的文章数：total 包含子分类中的文章，direct 只统计直接归属该分类的文章
+    pub fn category_counts(&self, project_root: &Path) -> Result<Vec<(Vec<String>, usize, usize)>, CommandError> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(
+            "SELECT c.path, COUNT(DISTINCT c.post_id), COUNT(DISTINCT CASE WHEN c.leaf = 1 THEN c.post_id END) \
+             FROM post_categories c JOIN posts p ON p.id = c.post_id \
+             WHERE p.project = ?1 GROUP BY c.path ORDER BY c.path",
+        )?;
+        let rows = statement.query_map([project_root.to_string_lossy()], |row| {
+            Ok((
+                split_category(&row.get::<_, String>(0)?),
+                row.get::<_, i64>(1)? as usize,
+                row.get::<_, i64>(2)? as usize,
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // 已发布文章的规范化日期、字数、标签和顶级分类，供发布统计使用
+    pub fn dated_posts(&self, project_root: &Path) -> Result<Vec<DatedPost>, CommandError> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement =
+            conn.prepare("SELECT id, date_sort, updated_sort, word_count FROM posts WHERE project = ?1 AND published = 1")?;
+        let rows = statement.query_map([project_root.to_string_lossy()], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                DatedPost {
+                    date: row.get(1)?,
+                    updated: row.get(2)?,
+                    word_count: row.get::<_, i64>(3)? as usize,
+                    tags: Vec::new(),
+                    categories: Vec::new(),
+                },
+            ))
+        })?;
+
+        let mut tags = conn.prepare_cached("SELECT DISTINCT name FROM post_tags WHERE post_id = ?1")?;
+        let mut categories = conn.prepare_cached("SELECT DISTINCT path FROM post_categories WHERE post_id = ?1 AND depth = 1")?;
+        let mut posts = Vec::new();
+        for row in rows {
+            let (id, mut post) = row?;
+            post.tags = tags.query_map([id], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+            post.categories = categories.query_map([id], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+            posts.push(post);
+        }
+        Ok(posts)
+    }
+}
+
+// 文件保存后更新索引，供 write_file 等命令调用；不在已登记的项目中时忽略
+pub fn reindex(store: &ContentStore, scope: &PathScope, path: &Path) -> Result<(), CommandError> {
+    match scope.root_of(path) {
+        Some(root) => store.index_file(&root, path),
+        None => Ok(()),
+    }
+}
+
+fn join_category(path: &[String]) -> String {
+    path.join(CATEGORY_SEPARATOR)
+}
+
+fn split_category(path: &str) -> Vec<String> {
+    path.split(CATEGORY_SEPARATOR).map(str::to_string).collect()
+}
+
+fn upsert_post(
+    conn: &Connection,
+    project: &str,
+    summary: &PostSummary,
+    document: &SearchDocument,
+    modified_ns: i64,
+) -> rusqlite::Result<()> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM posts WHERE project = ?1 AND relative_path = ?2",
+            params![project, summary.relative_path],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(id) = existing {
+        conn.execute("DELETE FROM posts WHERE id = ?1", [id])?;
+    }
+
+    conn.execute(
+        "INSERT INTO posts (project, relative_path, slug, title, date, updated, date_sort, updated_sort, \
+         draft, published, word_count, has_excerpt, size, modified_ns, error) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        params![
+            project,
+            summary.relative_path,
+            summary.slug,
+            summary.title,
+            summary.date,
+            summary.updated,
+            summary.date.as_deref().and_then(post_index::normalize_date),
+            summary.updated.as_deref().and_then(post_index::normalize_date),
+            summary.draft,
+            summary.published,
+            summary.word_count as i64,
+            summary.has_excerpt,
+            summary.size as i64,
+            modified_ns,
+            summary.error,
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    conn.execute(
+        "INSERT INTO post_search (rowid, title, front_matter, body) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            id,
+            search::index_text(&document.title),
+            search::index_text(&document.front_matter),
+            search::index_text(&document.body)
+        ],
+    )?;
+    conn.execute(
+        "INSERT INTO post_content (post_id, title, body) VALUES (?1, ?2, ?3)",
+        params![id, document.title, document.body],
+    )?;
+
+    for (position, tag) in summary.tags.iter().enumerate() {
+        conn.execute(
+            "INSERT INTO post_tags (post_id, position, name) VALUES (?1, ?2, ?3)",
+            params![id, position as i64, tag],
+        )?;
+    }
+    for (path_index, path) in summary.categories.iter().enumerate() {
+        for depth in 1..=path.len() {
+            conn.execute(
+                "INSERT INTO post_categories (post_id, path_index, depth, path, leaf) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, path_index as i64, depth as i64, join_category(&path[..depth]), depth == path.len()],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn row_to_summary(row: &Row, display_root: &Path) -> rusqlite::Result<(i64, PostSummary)> {
+    let relative_path: String = row.get(1)?;
+    let modified_ns: i64 = row.get(11)?;
+    Ok((
+        row.get(0)?,
+        PostSummary {
+            path: display_root.join(&relative_path).to_string_lossy().to_string(),
+            relative_path,
+            slug: row.get(2)?,
+            title: row.get(3)?,
+            date: row.get(4)?,
+            updated: row.get(5)?,
+            tags: Vec::new(),
+            categories: Vec::new(),
+            draft: row.get(6)?,
+            published: row.get(7)?,
+            word_count: row.get::<_, i64>(8)? as usize,
+            has_excerpt: row.get(9)?,
+            size: row.get::<_, i64>(10)? as u64,
+            modified_time: (modified_ns / 1_000_000).to_string(),
+            error: row.get(12)?,
+        },
+    ))
+}
+
+fn load_taxonomies(conn: &Connection, id: i64, summary: &mut PostSummary) -> rusqlite::Result<()> {
+    let mut tags = conn.prepare_cached("SELECT name FROM post_tags WHERE post_id = ?1 ORDER BY position")?;
+    summary.tags = tags.query_map([id], |row| row.get(0))?.collect::<rusqlite::Result<_>>()?;
+
+    let mut categories =
+        conn.prepare_cached("SELECT path FROM post_categories WHERE post_id = ?1 AND leaf = 1 ORDER BY path_index")?;
+    summary.categories = categories
+        .query_map([id], |row| row.get::<_, String>(0))?
+        .map(|path| path.map(|path| split_category(&path)))
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(())
+}
+
+// 刷新索引后按条件分页查询文章
+#[tauri::command]
+pub async fn query_posts(
+    project_path: String,
+    query: Option<PostQuery>,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<PostPage, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let mut query = query.unwrap_or_default();
+    query.page_size = Some(query.page_size.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE));
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        store.refresh(&project_root)?;
+        store.query(&project_root, Path::new(&project_path), &query)
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_names_may_contain_slashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("blog");
+        let posts = project.join("source").join("_posts");
+        fs::create_dir_all(&posts).unwrap();
+        fs::write(posts.join("a.md"), "---\ntitle: a\ncategories: [\"A/B\", C]\n---\nbody\n").unwrap();
+        fs::write(posts.join("b.md"), "---\ntitle: b\ncategories: [A, B]\n---\nbody\n").unwrap();
+
+        let store = ContentStore::open(&dir.path().join("index.db")).unwrap();
+        store.refresh(&project).unwrap();
+
+        let counts: Vec<(Vec<String>, usize, usize)> = store.category_counts(&project).unwrap();
+        let path = |parts: &[&str]| parts.iter().map(|part| part.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            counts,
+            vec![
+                (path(&["A"]), 1, 0),
+                (path(&["A", "B"]), 1, 1),
+                (path(&["A/B"]), 1, 0),
+                (path(&["A/B", "C"]), 1, 1),
+            ]
+        );
+
+        let query = PostQuery {
+            category: Some(path(&["A/B"])),
+            ..Default::default()
+        };
+        let page = store.query(&project, &project, &query).unwrap();
+        assert_eq!(page.posts.len(), 1);
+        assert_eq!(page.posts[0].categories, vec![path(&["A/B", "C"])]);
+    }
+
+    #[test]
+    fn corrupt_database_is_recreated() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("blog");
+        let posts = project.join("source").join("_posts");
+        fs::create_dir_all(&posts).unwrap();
+        fs::write(posts.join("a.md"), "---\ntitle: a\n---\nbody\n").unwrap();
+        let db = dir.path().join("content.db");
+        fs::write(&db, "not a sqlite database, just some bytes that are long enough").unwrap();
+
+        assert!(ContentStore::open(&db).is_err());
+        let store = ContentStore::open_or_recreate(&db).unwrap();
+        assert_eq!(store.refresh(&project).unwrap().parsed, 1);
+        drop(store);
+        assert!(ContentStore::open(&db).is_ok());
+    }
+}
@@ -0,0 +1,387 @@
+// Hexo 渲染错误诊断
+// 将 hexo generate / server 输出中的 YAML front-matter 错误和 Nunjucks 标签错误
+// 解析为带有项目相对路径和行列号的诊断信息，供编辑器直接跳转到出错位置
+
+use std::fs;
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::error::CommandError;
+use crate::scope::PathScope;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexoDiagnostic {
+    // "yaml" 或 "nunjucks"
+    pub kind: String,
+    // 项目相对路径（统一使用 / 分隔），无法确定文件时为 None
+    pub file: Option<String>,
+    // 行列号均从 1 开始
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub message: String,
+}
+
+struct Patterns {
+    process_failed: Regex,
+    yaml_exception: Regex,
+    yaml_position_v3: Regex,
+    yaml_position_v4: Regex,
+    template_error: Regex,
+    nunjucks_error: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        Patterns {
+            // Hexo: "ERROR Process failed: _posts/hello.md"
+            process_failed: Regex::new(r"Process failed:\s*(\S.*?)\s*$").unwrap(),
+            yaml_exception: Regex::new(r"YAMLException:\s*(.*)$").unwrap(),
+            // js-yaml 3.x: "... at line 4, column 1:"
+            yaml_position_v3: Regex::new(r"\s*at line (\d+), column (\d+):?\s*$").unwrap(),
+            // js-yaml 4.x: "... (4:1)"
+            yaml_position_v4: Regex::new(r"\s*\((\d+):(\d+)\)\s*$").unwrap(),
+            // Nunjucks: "Template render error: (/path/to/post.md) [Line 3, Column 5]"
+            template_error: Regex::new(
+                r"Template render error:\s*\(([^)]*)\)(?:\s*\[Line (\d+), Column (\d+)\])?\s*(.*)$",
+            )
+            .unwrap(),
+            // Hexo 5+: "Nunjucks Error: _posts/foo.md [Line 3, Column 5] unknown block tag: foo"
+            nunjucks_error: Regex::new(
+                r"Nunjucks Error:\s*(\S.*?)\s*\[Line (\d+), Column (\d+)\]\s*(.*)$",
+            )
+            .unwrap(),
+        }
+    }
+}
+
+// 去除 ANSI 颜色转义序列（hexo 输出默认带颜色）
+fn strip_ansi(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' && chars.peek() == Some(&'[') {
+            chars.next();
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// 将错误输出中的路径转换为项目相对路径
+// Hexo 报告的路径可能是绝对路径，也可能是相对于 source 目录的路径
+pub(crate) fn to_project_relative(raw: &str, project_root: &Path) -> Option<String> {
+    let raw = raw.trim();
+    if raw.is_empty() || raw == "unknown path" {
+        return None;
+    }
+
+    let path = Path::new(raw);
+    if path.is_absolute() {
+        let relative = match path.strip_prefix(project_root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => {
+                let canonical = fs::canonicalize(project_root).ok()?;
+                path.strip_prefix(canonical).ok()?.to_path_buf()
+            }
+        };
+        return Some(relative.to_string_lossy().replace('\\', "/"));
+    }
+
+    let normalized = raw.replace('\\', "/");
+    let normalized = normalized.trim_start_matches("./");
+    if normalized.starts_with("source/") || normalized.starts_with("themes/") || normalized.starts_with("scaffolds/") {
+        Some(normalized.to_string())
+    } else {
+        Some(format!("source/{}", normalized))
+    }
+}
+
+// js-yaml 的行号是相对于 front-matter 内容的，需要加上开头 "---" 分隔行的偏移
+// 文件路径来自命令输出，读取前同样需要经过访问范围检查，不在范围内时不做偏移
+fn yaml_line_offset(scope: &PathScope, project_root: &Path, file: &str) -> u32 {
+    let content = scope
+        .resolve(&project_root.join(file).to_string_lossy())
+        .and_then(|path| Ok(fs::read_to_string(path)?));
+    match content {
+        Ok(content) => {
+            let first_line = content.trim_start_matches('\u{feff}').lines().next().unwrap_or("");
+            if first_line.trim_end() == "---" || first_line.trim_end() == ";;;" {
+                1
+            } else {
+                0
+            }
+        }
+        Err(_) => 0,
+    }
+}
+
+// 解析 Hexo 命令输出，提取可定位的诊断信息
+pub fn parse_hexo_errors(output: &str, project_root: &Path, scope: &PathScope) -> Vec<HexoDiagnostic> {
+    let patterns = Patterns::new();
+    let cleaned = strip_ansi(output);
+    let lines: Vec<&str> = cleaned.lines().collect();
+
+    let mut diagnostics: Vec<HexoDiagnostic> = Vec::new();
+    // Hexo 5+ 在 "Nunjucks Error:" 之前还会打印错误对象（"err: [Template render error: (unknown path)"），
+    // 其中没有文件和行号，有 "Nunjucks Error:" 行时不再单独报告
+    let has_nunjucks_error = lines.iter().any(|line| patterns.nunjucks_error.is_match(line));
+    // 最近一次 "Process failed" 报告的文件，用于关联后续的 YAML 错误
+    let mut current_file: Option<String> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(caps) = patterns.process_failed.captures(line) {
+            current_file = to_project_relative(&caps[1], project_root);
+            continue;
+        }
+
+        if let Some(caps) = patterns.yaml_exception.captures(line) {
+            let mut message = caps[1].trim().to_string();
+            let mut position = None;
+            for pattern in [&patterns.yaml_position_v3, &patterns.yaml_position_v4] {
+                if let Some(pos) = pattern.captures(&message) {
+                    position = Some((
+                        pos[1].parse::<u32>().unwrap_or(0),
+                        pos[2].parse::<u32>().unwrap_or(0),
+                    ));
+                    message = pattern.replace(&message, "").trim().to_string();
+                    break;
+                }
+            }
+
+            let (line_no, column) = match (position, &current_file) {
+                (Some((line_no, column)), Some(file)) => {
+                    (Some(line_no + yaml_line_offset(scope, project_root, file)), Some(column))
+                }
+                (Some((line_no, column)), None) => (Some(line_no), Some(column)),
+                (None, _) => (None, None),
+            };
+
+            diagnostics.push(HexoDiagnostic {
+                kind: "yaml".to_string(),
+                file: current_file.clone(),
+                line: line_no,
+                column,
+                message,
+            });
+            continue;
+        }
+
+        if let Some(caps) = patterns.nunjucks_error.captures(line) {
+            diagnostics.push(HexoDiagnostic {
+                kind: "nunjucks".to_string(),
+                file: to_project_relative(&caps[1], project_root),
+                line: caps[2].parse().ok(),
+                column: caps[3].parse().ok(),
+                message: caps[4].trim().to_string(),
+            });
+            continue;
+        }
+
+        if let Some(caps) = patterns.template_error.captures(line) {
+            if has_nunjucks_error && line.trim_start().starts_with("err:") {
+                continue;
+            }
+            let file = to_project_relative(&caps[1], project_root).or_else(|| current_file.clone());
+            // 具体的错误描述通常在下一行（缩进输出）
+            let mut message = caps.get(4).map(|m| m.as_str().trim().to_string()).unwrap_or_default();
+            if message.is_empty() {
+                message = lines
+                    .iter()
+                    .skip(index + 1)
+                    .map(|l| l.trim())
+                    .find(|l| !l.is_empty())
+                    .unwrap_or("")
+                    .to_string();
+            }
+
+            diagnostics.push(HexoDiagnostic {
+                kind: "nunjucks".to_string(),
+                file,
+                line: caps.get(2).and_then(|m| m.as_str().parse().ok()),
+                column: caps.get(3).and_then(|m| m.as_str().parse().ok()),
+                message,
+            });
+        }
+    }
+
+    // Hexo 有时会重复打印同一个错误
+    let mut unique: Vec<HexoDiagnostic> = Vec::with_capacity(diagnostics.len());
+    for diagnostic in diagnostics {
+        if !unique.contains(&diagnostic) {
+            unique.push(diagnostic);
+        }
+    }
+    unique
+}
+
+// 解析任意 Hexo 输出（供前端在 execute_command 等场景下使用）
+#[tauri::command]
+pub async fn parse_hexo_output(output: String, working_dir: String, scope: State<'_, PathScope>) -> Result<Vec<HexoDiagnostic>, CommandError> {
+    let project_root = scope.resolve(&working_dir)?;
+    Ok(parse_hexo_errors(&output, &project_root, &scope))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // hexo 4（js-yaml 3.x），front-matter 中 tags 的方括号未闭合
+    const YAML_V3_OUTPUT: &str = "\u{1b}[32mINFO\u{1b}[39m  Start processing
+\u{1b}[31mERROR\u{1b}[39m Process failed: _posts/hello-world.md
+YAMLException: can not read a block mapping entry; a multiline key may not be an implicit key at line 3, column 5:
+    date: 2020-01-01 12:00:00
+        ^
+    at generateError (/home/user/blog/node_modules/js-yaml/lib/js-yaml/loader.js:167:10)
+    at throwError (/home/user/blog/node_modules/js-yaml/lib/js-yaml/loader.js:173:9)
+    at readBlockMapping (/home/user/blog/node_modules/js-yaml/lib/js-yaml/loader.js:1073:9)";
+
+    // hexo 6（js-yaml 4.x），位置信息改为 (行:列) 并附带代码片段
+    const YAML_V4_OUTPUT: &str = "INFO  Start processing
+ERROR Process failed: _posts/hello-world.md
+YAMLException: bad indentation of a mapping entry (3:6)
+
+ 1 | title: Hello World
+ 2 | date: 2023-05-01 10:00:00
+ 3 | tags: foo
+----------^
+ 4 | ---
+    at generateError (/home/user/blog/node_modules/js-yaml/dist/js-yaml.js:1273:10)
+    at throwError (/home/user/blog/node_modules/js-yaml/dist/js-yaml.js:1279:9)";
+
+    // hexo 4 的 Nunjucks 错误，错误描述在下一行
+    const TEMPLATE_RENDER_OUTPUT: &str = "INFO  Start processing
+FATAL Something's wrong. Maybe you can find the solution here: https://hexo.io/docs/troubleshooting.html
+Template render error: (unknown path) [Line 5, Column 3]
+  unknown block tag: note
+    at Object._prettifyError (/home/user/blog/node_modules/nunjucks/src/lib.js:36:11)
+    at Template.render (/home/user/blog/node_modules/nunjucks/src/environment.js:542:21)";
+
+    // hexo 5+ 的 Nunjucks 错误
+    const NUNJUCKS_OUTPUT: &str = "INFO  Start processing
+FATAL {
+  err: [Template render error: (unknown path)
+    Error: Unable to call `the return value of (post_link)`, which is undefined or falsey
+  ] {
+    lineno: undefined,
+    colno: undefined
+  }
+} Something's wrong. Maybe you can find the solution here: %s https://hexo.io/docs/troubleshooting.html
+Nunjucks Error: _posts/broken-tag.md [Line 12, Column 4] unknown block tag: notee";
+
+    fn project() -> (tempfile::TempDir, PathScope) {
+        let dir = tempfile::tempdir().unwrap();
+        let posts = dir.path().join("source").join("_posts");
+        fs::create_dir_all(&posts).unwrap();
+        fs::write(posts.join("hello-world.md"), "---\ntitle: Hello World\ndate: 2020-01-01\n---\n").unwrap();
+        let scope = PathScope::new();
+        scope.add_root(dir.path()).unwrap();
+        (dir, scope)
+    }
+
+    #[test]
+    fn parses_js_yaml_v3_position() {
+        let (dir, scope) = project();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let diagnostics = parse_hexo_errors(YAML_V3_OUTPUT, &root, &scope);
+        assert_eq!(
+            diagnostics,
+            vec![HexoDiagnostic {
+                kind: "yaml".to_string(),
+                file: Some("source/_posts/hello-world.md".to_string()),
+                // 加上开头 --- 所在的一行
+                line: Some(4),
+                column: Some(5),
+                message: "can not read a block mapping entry; a multiline key may not be an implicit key".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_js_yaml_v4_position() {
+        let (dir, scope) = project();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let diagnostics = parse_hexo_errors(YAML_V4_OUTPUT, &root, &scope);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file.as_deref(), Some("source/_posts/hello-world.md"));
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(4), Some(6)));
+        assert_eq!(diagnostics[0].message, "bad indentation of a mapping entry");
+    }
+
+    #[test]
+    fn yaml_offset_skipped_outside_scope() {
+        let (dir, _) = project();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        // 项目未注册时不读取文件，行号保持 js-yaml 的原始值
+        let diagnostics = parse_hexo_errors(YAML_V4_OUTPUT, &root, &PathScope::new());
+        assert_eq!(diagnostics[0].line, Some(3));
+    }
+
+    #[test]
+    fn parses_template_render_error() {
+        let (dir, scope) = project();
+        let diagnostics = parse_hexo_errors(TEMPLATE_RENDER_OUTPUT, dir.path(), &scope);
+        assert_eq!(
+            diagnostics,
+            vec![HexoDiagnostic {
+                kind: "nunjucks".to_string(),
+                file: None,
+                line: Some(5),
+                column: Some(3),
+                message: "unknown block tag: note".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_nunjucks_error() {
+        let (dir, scope) = project();
+        let diagnostics = parse_hexo_errors(NUNJUCKS_OUTPUT, dir.path(), &scope);
+        assert_eq!(
+            diagnostics,
+            vec![HexoDiagnostic {
+                kind: "nunjucks".to_string(),
+                file: Some("source/_posts/broken-tag.md".to_string()),
+                line: Some(12),
+                column: Some(4),
+                message: "unknown block tag: notee".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn error_object_alone_is_still_reported() {
+        let (dir, scope) = project();
+        let output = NUNJUCKS_OUTPUT.lines().filter(|line| !line.starts_with("Nunjucks Error:")).collect::<Vec<_>>().join("\n");
+        let diagnostics = parse_hexo_errors(&output, dir.path(), &scope);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, None);
+        assert_eq!(
+            diagnostics[0].message,
+            "Error: Unable to call `the return value of (post_link)`, which is undefined or falsey"
+        );
+    }
+
+    #[test]
+    fn process_failed_with_absolute_path() {
+        let (dir, scope) = project();
+        let root = dunce::canonicalize(dir.path()).unwrap();
+        let absolute = root.join("source").join("_posts").join("hello-world.md");
+        let output = format!(
+            "ERROR Process failed: {}\nYAMLException: end of the stream or a document separator is expected at line 2, column 1:",
+            absolute.display()
+        );
+        let diagnostics = parse_hexo_errors(&output, &root, &scope);
+        assert_eq!(diagnostics[0].file.as_deref(), Some("source/_posts/hello-world.md"));
+        assert_eq!(diagnostics[0].line, Some(3));
+    }
+}
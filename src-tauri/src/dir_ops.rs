@@ -0,0 +1,293 @@
+// 目录操作
+// 创建目录、复制目录树（支持覆盖/跳过策略和进度回报）、删除目录（移入回收站）和统计目录大小
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State};
+use walkdir::WalkDir;
+
+use crate::error::{self, CommandError};
+use crate::recycle_bin;
+use crate::scope::PathScope;
+
+// 进度回报的最小间隔，避免大量小文件时刷屏
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// 目标位置已有同名文件时的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    // 中止复制并返回 ALREADY_EXISTS
+    #[default]
+    Error,
+    Overwrite,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyProgress {
+    pub copied_files: usize,
+    pub total_files: usize,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub current_path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyDirResult {
+    pub copied_files: usize,
+    pub skipped_files: Vec<String>,
+    pub copied_bytes: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirSize {
+    pub bytes: u64,
+    pub files: usize,
+    pub directories: usize,
+}
+
+// 递归复制文件或目录
+pub(crate) fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(from, to).map(|_| ())
+    }
+}
+
+// 移动文件或目录；跨文件系统时 rename 会失败，退化为复制后删除
+pub(crate) fn move_path(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::rename(from, to) {
+        Ok(()) => return Ok(()),
+        // 只有跨设备（跨分区）移动才退回到复制 + 删除，其他错误（权限、目标已存在等）直接返回
+        Err(e) if is_cross_device(&e) => {}
+        Err(e) => return Err(e),
+    }
+
+    copy_tree(from, to)?;
+    if from.is_dir() {
+        fs::remove_dir_all(from)
+    } else {
+        fs::remove_file(from)
+    }
+}
+
+// rename 因源和目标不在同一文件系统而失败（ErrorKind::CrossesDevices 在最低支持的 Rust 版本中尚未稳定）
+fn is_cross_device(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    const CROSS_DEVICE: i32 = 18; // EXDEV
+    #[cfg(windows)]
+    const CROSS_DEVICE: i32 = 17; // ERROR_NOT_SAME_DEVICE
+    #[cfg(not(any(unix, windows)))]
+    const CROSS_DEVICE: i32 = -1;
+    e.raw_os_error() == Some(CROSS_DEVICE)
+}
+
+fn measure(path: &Path) -> DirSize {
+    let mut size = DirSize::default();
+    // 不跟随符号链接，避免统计到项目外的内容
+    for entry in WalkDir::new(path).min_depth(1).follow_links(false).into_iter().flatten() {
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            size.directories += 1;
+        } else if file_type.is_file() {
+            size.files += 1;
+            size.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    size
+}
+
+fn copy_dir_with_progress(
+    source: &Path,
+    destination: &Path,
+    policy: ConflictPolicy,
+    mut on_progress: impl FnMut(CopyProgress),
+) -> Result<CopyDirResult, CommandError> {
+    let total = measure(source);
+    let mut result = CopyDirResult {
+        copied_files: 0,
+        skipped_files: Vec::new(),
+        copied_bytes: 0,
+    };
+    let mut last_report = Instant::now();
+
+    fs::create_dir_all(destination)?;
+    for entry in WalkDir::new(source).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let target = destination.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        // 符号链接不复制，避免把项目外的内容带进来
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        if target.exists() {
+            match policy {
+                ConflictPolicy::Error => {
+                    return Err(CommandError::new(
+                        error::ALREADY_EXISTS,
+                        format!("目标文件已存在: {}", target.display()),
+                    ));
+                }
+                ConflictPolicy::Skip => {
+                    result.skipped_files.push(target.to_string_lossy().to_string());
+                    continue;
+                }
+                ConflictPolicy::Overwrite => {}
+            }
+        }
+
+        result.copied_bytes += fs::copy(entry.path(), &target)?;
+        result.copied_files += 1;
+
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            on_progress(CopyProgress {
+                copied_files: result.copied_files,
+                total_files: total.files,
+                copied_bytes: result.copied_bytes,
+                total_bytes: total.bytes,
+                current_path: target.to_string_lossy().to_string(),
+            });
+        }
+    }
+
+    // 结束时总是回报一次最终进度
+    on_progress(CopyProgress {
+        copied_files: result.copied_files,
+        total_files: total.files,
+        copied_bytes: result.copied_bytes,
+        total_bytes: total.bytes,
+        current_path: destination.to_string_lossy().to_string(),
+    });
+    Ok(result)
+}
+
+// 创建目录（已存在时返回 ALREADY_EXISTS，需要幂等创建请使用 ensure_dir）
+#[tauri::command]
+pub async fn create_dir(directory_path: String, scope: State<'_, PathScope>) -> Result<String, CommandError> {
+    let path = scope.resolve(&directory_path)?;
+    if path.exists() {
+        return Err(CommandError::new(
+            error::ALREADY_EXISTS,
+            format!("目录已存在: {}", directory_path),
+        ));
+    }
+    fs::create_dir_all(&path)?;
+    Ok(directory_path)
+}
+
+// 复制目录树，on_progress 接收复制进度
+#[tauri::command]
+pub async fn copy_dir(
+    source_path: String,
+    destination_path: String,
+    conflict_policy: Option<ConflictPolicy>,
+    on_progress: Channel<CopyProgress>,
+    scope: State<'_, PathScope>,
+) -> Result<CopyDirResult, CommandError> {
+    let source = scope.resolve(&source_path)?;
+    let destination = scope.resolve(&destination_path)?;
+
+    if !source.is_dir() {
+        return Err(CommandError::new(error::NOT_FOUND, format!("目录不存在: {}", source_path)));
+    }
+    if destination.starts_with(&source) {
+        return Err(CommandError::new(error::INVALID_PATH, "不能将目录复制到其自身内部"));
+    }
+
+    let policy = conflict_policy.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        copy_dir_with_progress(&source, &destination, policy, |progress| {
+            let _ = on_progress.send(progress);
+        })
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+// 删除目录（移入回收站）
+#[tauri::command]
+pub async fn remove_dir(
+    directory_path: String,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<bool, CommandError> {
+    let path = scope.resolve(&directory_path)?;
+    if !path.is_dir() {
+        return Err(CommandError::new(error::NOT_FOUND, format!("目录不存在: {}", directory_path)));
+    }
+    // 不允许删除已注册的项目根目录本身
+    if scope.root_of(&path).as_deref() == Some(path.as_path()) {
+        return Err(CommandError::new(error::INVALID_PATH, "不能删除项目根目录"));
+    }
+
+    recycle_bin::move_to_trash(&app_handle, &[path]).map(|_| true)
+}
+
+// 统计目录大小（字节数、文件数、子目录数）
+#[tauri::command]
+pub async fn dir_size(directory_path: String, scope: State<'_, PathScope>) -> Result<DirSize, CommandError> {
+    let path: PathBuf = scope.resolve(&directory_path)?;
+    if !path.is_dir() {
+        return Err(CommandError::new(error::NOT_FOUND, format!("目录不存在: {}", directory_path)));
+    }
+    tauri::async_runtime::spawn_blocking(move || measure(&path))
+        .await
+        .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_within_the_same_filesystem() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("x.png"), "x").unwrap();
+        let to = dir.path().join("nested").join("b");
+        move_path(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(to.join("x.png")).unwrap(), "x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_fall_back_to_copy_on_other_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("a");
+        let to = dir.path().join("b");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("x.png"), "x").unwrap();
+        fs::create_dir_all(&to).unwrap();
+        fs::write(to.join("y.png"), "y").unwrap();
+        // 目标是非空目录时 rename 失败，不能合并后删除源目录
+        assert!(move_path(&from, &to).is_err());
+        assert!(from.join("x.png").is_file());
+        assert!(!to.join("x.png").exists());
+    }
+}
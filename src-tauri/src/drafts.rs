@@ -0,0 +1,351 @@
+// 草稿管理
+// 列出 source/_drafts 中的草稿；发布时写入发布时间，按 _config.yml 的 new_post_name
+// 把草稿及其资源文件夹移动到 source/_posts（与 hexo publish 一致），取消发布则移回 _drafts
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+use crate::atomic_write::WriteOptions;
+use crate::content_store::{ContentStore, PostQuery};
+use crate::error::{self, CommandError};
+use crate::file_meta;
+use crate::front_matter::update_front_matter_text;
+use crate::history::VersionHistory;
+use crate::post_index::{normalize_date, PostSummary};
+use crate::post_move::{is_markdown, normalize_lexically, relocate_post, Relocated};
+use crate::save_file;
+use crate::scope::PathScope;
+use crate::taxonomy::SkippedPost;
+use crate::text_encoding;
+
+// Hexo 的默认值
+const DEFAULT_NEW_POST_NAME: &str = ":title.md";
+pub(crate) const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishResult {
+    pub new_path: String,
+    pub new_asset_folder: Option<String>,
+    // 因链接改写而被修改的其他文件
+    pub changed_files: Vec<String>,
+    // 无法读取或解码、链接未能更新的文件
+    pub skipped_files: Vec<SkippedPost>,
+    // 发布时写入的 date（取消发布时为 None）
+    pub date: Option<String>,
+}
+
+// 读取站点配置 _config.yml，不存在或无法解析时返回 Null
+pub(crate) fn site_config(project_root: &Path) -> serde_yaml::Value {
+    fs::read_to_string(project_root.join("_config.yml"))
+        .ok()
+        .and_then(|content| serde_yaml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+// _config.yml 中的 new_post_name
+pub(crate) fn new_post_name(config: &serde_yaml::Value) -> String {
+    config
+        .get("new_post_name")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(DEFAULT_NEW_POST_NAME)
+        .to_string()
+}
+
+// 展开 new_post_name 中的占位符（:title、:year、:month、:i_month、:day、:i_day、:hour、:minute、:second）；
+// 没有扩展名时补上 .md
+pub(crate) fn render_post_name(pattern: &str, title: &str, date: &NaiveDateTime) -> String {
+    static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+    let placeholder = PLACEHOLDER.get_or_init(|| Regex::new(r":(i_month|i_day|title|year|month|day|hour|minute|second)").unwrap());
+    let name = placeholder
+        .replace_all(pattern, |caps: &Captures| match &caps[1] {
+            "title" => title.to_string(),
+            "year" => date.year().to_string(),
+            "month" => format!("{:02}", date.month()),
+            "i_month" => date.month().to_string(),
+            "day" => format!("{:02}", date.day()),
+            "i_day" => date.day().to_string(),
+            "hour" => format!("{:02}", date.hour()),
+            "minute" => format!("{:02}", date.minute()),
+            _ => format!("{:02}", date.second()),
+        })
+        .to_string();
+    if is_markdown(Path::new(&name)) {
+        name
+    } else {
+        format!("{}.md", name)
+    }
+}
+
+// 解析调用方传入的日期，未传入时使用当前本地时间
+pub(crate) fn publish_date(date: Option<&str>) -> Result<NaiveDateTime, CommandError> {
+    match date {
+        Some(date) => normalize_date(date)
+            .and_then(|normalized| NaiveDateTime::parse_from_str(&normalized, DATE_FORMAT).ok())
+            .ok_or_else(|| CommandError::new(error::INVALID_ARGUMENT, format!("无法识别的日期: {}", date))),
+        None => Ok(Local::now().naive_local().with_nanosecond(0).unwrap_or_default()),
+    }
+}
+
+fn ensure_within(path: &Path, dir: &Path, message: &str) -> Result<(), CommandError> {
+    if !path.starts_with(dir) || !is_markdown(path) || !path.is_file() {
+        return Err(CommandError::new(error::INVALID_PATH, message.to_string()));
+    }
+    Ok(())
+}
+
+// 在文件原有的编码和换行符下修改 front-matter（与 write_file 一样保存版本历史并更新索引）
+fn set_front_matter(
+    history: &VersionHistory,
+    scope: &PathScope,
+    store: &ContentStore,
+    path: &Path,
+    updates: &Map<String, Value>,
+    remove_keys: &[String],
+) -> Result<(), CommandError> {
+    let _guard = file_meta::write_lock();
+    let bytes = fs::read(path)?;
+    let decoded = text_encoding::decode(&bytes)?;
+    let text = update_front_matter_text(&decoded.content, updates, remove_keys)?;
+    let new_bytes = text_encoding::encode(&text, decoded.encoding, decoded.line_ending);
+    if new_bytes != bytes {
+        save_file(history, scope, store, path, Some(&bytes), &new_bytes, WriteOptions::default())?;
+    }
+    Ok(())
+}
+
+fn to_result(new_post: PathBuf, relocated: Relocated, date: Option<String>) -> PublishResult {
+    PublishResult {
+        new_path: new_post.to_string_lossy().to_string(),
+        new_asset_folder: relocated.new_assets.map(|p| p.to_string_lossy().to_string()),
+        changed_files: relocated
+            .changed_files
+            .into_iter()
+            .filter(|file| Path::new(file) != new_post)
+            .collect(),
+        skipped_files: relocated.skipped_files,
+        date,
+    }
+}
+
+// 列出项目中的草稿（按修改时间从新到旧）
+#[tauri::command]
+pub async fn list_drafts(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<Vec<PostSummary>, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        store.refresh(&project_root)?;
+        let query = PostQuery {
+            draft: Some(true),
+            sort_by: Some("modifiedTime".to_string()),
+            sort_order: Some("desc".to_string()),
+            page_size: Some(usize::MAX),
+            ..PostQuery::default()
+        };
+        Ok(store.query(&project_root, Path::new(&project_path), &query)?.posts)
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+// 发布草稿：写入 date（默认当前时间），按 new_post_name 移动到 source/_posts，资源文件夹一并移动
+#[tauri::command]
+pub async fn publish_draft(
+    project_path: String,
+    draft_path: String,
+    date: Option<String>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<PublishResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let draft = scope.resolve(&draft_path)?;
+    let date = publish_date(date.as_deref())?;
+    publish(&history, &scope, &store, &project_root, &draft, &date, &[])
+}
+
+// 发布草稿并写入 date，同时删除 remove_keys 中的 front-matter 键（定时发布用来去掉 publish_at）
+pub(crate) fn publish(
+    history: &VersionHistory,
+    scope: &PathScope,
+    store: &ContentStore,
+    project_root: &Path,
+    draft: &Path,
+    date: &NaiveDateTime,
+    remove_keys: &[String],
+) -> Result<PublishResult, CommandError> {
+    let drafts_dir = project_root.join("source").join("_drafts");
+    ensure_within(draft, &drafts_dir, "只能发布 source/_drafts 中的 markdown 文件")?;
+
+    let date_text = date.format(DATE_FORMAT).to_string();
+    let mut updates = Map::new();
+    updates.insert("date".to_string(), Value::String(date_text.clone()));
+
+    // 移动前先确认 front-matter 可以修改，避免移动后才失败
+    let decoded = text_encoding::decode(&fs::read(draft)?)?;
+    update_front_matter_text(&decoded.content, &updates, remove_keys)?;
+
+    // 与 hexo publish 一致，:title 取草稿的文件名
+    let title = draft
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = render_post_name(&new_post_name(&site_config(project_root)), &title, date);
+    let posts_dir = project_root.join("source").join("_posts");
+    let new_post = normalize_lexically(&posts_dir.join(name.trim_start_matches('/')));
+    if !new_post.starts_with(&posts_dir) {
+        return Err(CommandError::new(error::INVALID_PATH, format!("new_post_name 生成了无效的路径: {}", name)));
+    }
+
+    let relocated = relocate_post(history, scope, store, project_root, draft, &new_post)?;
+    set_front_matter(history, scope, store, &new_post, &updates, remove_keys)?;
+    Ok(to_result(new_post, relocated, Some(date_text)))
+}
+
+// 取消发布：把 source/_posts 中的文章及其资源文件夹移回 source/_drafts（front-matter 保持不变，重新发布时会写入新的 date）
+#[tauri::command]
+pub async fn unpublish_post(
+    project_path: String,
+    post_path: String,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<PublishResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let post = scope.resolve(&post_path)?;
+    unpublish(&history, &scope, &store, &project_root, &post)
+}
+
+pub(crate) fn unpublish(
+    history: &VersionHistory,
+    scope: &PathScope,
+    store: &ContentStore,
+    project_root: &Path,
+    post: &Path,
+) -> Result<PublishResult, CommandError> {
+    let posts_dir = project_root.join("source").join("_posts");
+    ensure_within(post, &posts_dir, "只能取消发布 source/_posts 中的 markdown 文件")?;
+
+    // new_post_name 可能带有 :year/:month 等目录，草稿统一放在 _drafts 顶层
+    let file_name = post
+        .file_name()
+        .ok_or_else(|| CommandError::new(error::INVALID_PATH, format!("无效的文章路径: {}", post.display())))?;
+    let new_draft = project_root.join("source").join("_drafts").join(file_name);
+
+    let relocated = relocate_post(history, scope, store, project_root, post, &new_draft)?;
+    Ok(to_result(new_draft, relocated, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Project {
+        _dir: tempfile::TempDir,
+        root: PathBuf,
+        scope: PathScope,
+        history: VersionHistory,
+        store: ContentStore,
+    }
+
+    fn project(config: &str) -> Project {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("blog");
+        fs::create_dir_all(root.join("source").join("_drafts")).unwrap();
+        fs::create_dir_all(root.join("source").join("_posts")).unwrap();
+        fs::write(root.join("_config.yml"), config).unwrap();
+        let scope = PathScope::new();
+        let root = scope.add_root(&root).unwrap();
+        let history = VersionHistory::new(dir.path().join("history"));
+        let store = ContentStore::open(&dir.path().join("content.db")).unwrap();
+        Project { _dir: dir, root, scope, history, store }
+    }
+
+    fn date() -> NaiveDateTime {
+        NaiveDateTime::parse_from_str("2024-03-05 08:09:07", DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn render_post_name_expands_placeholders() {
+        assert_eq!(
+            render_post_name(":year/:month/:day/:title", "hello", &date()),
+            "2024/03/05/hello.md"
+        );
+        assert_eq!(
+            render_post_name(":i_month-:i_day-:hour:minute:second-:title.md", "hello", &date()),
+            "3-5-080907-hello.md"
+        );
+        assert_eq!(render_post_name(":title.markdown", "hello", &date()), "hello.markdown");
+    }
+
+    #[test]
+    fn new_post_name_falls_back_to_default() {
+        assert_eq!(new_post_name(&serde_yaml::Value::Null), DEFAULT_NEW_POST_NAME);
+        let config: serde_yaml::Value = serde_yaml::from_str("new_post_name: ' '").unwrap();
+        assert_eq!(new_post_name(&config), DEFAULT_NEW_POST_NAME);
+    }
+
+    #[test]
+    fn publish_rejects_names_outside_posts_dir() {
+        let p = project("new_post_name: ../../:title.md\n");
+        let draft = p.root.join("source").join("_drafts").join("a.md");
+        fs::write(&draft, "---\ntitle: a\n---\nbody\n").unwrap();
+
+        let Err(error) = publish(&p.history, &p.scope, &p.store, &p.root, &draft, &date(), &[]) else {
+            panic!("publish should reject an escaping new_post_name");
+        };
+        assert_eq!(error.code, error::INVALID_PATH);
+        assert!(draft.is_file());
+        assert!(!p.root.join("a.md").exists());
+    }
+
+    #[test]
+    fn publish_and_unpublish_move_post_and_assets() {
+        let p = project("new_post_name: :year/:title.md\n");
+        let drafts_dir = p.root.join("source").join("_drafts");
+        let draft = drafts_dir.join("a.md");
+        fs::write(&draft, "---\ntitle: a\npublish_at: 2024-03-05 08:00:00\n---\n![](a/img.png)\n").unwrap();
+        fs::create_dir_all(drafts_dir.join("a")).unwrap();
+        fs::write(drafts_dir.join("a").join("img.png"), b"png").unwrap();
+
+        let published = publish(
+            &p.history,
+            &p.scope,
+            &p.store,
+            &p.root,
+            &draft,
+            &date(),
+            &["publish_at".to_string()],
+        )
+        .unwrap();
+        let post = p.root.join("source").join("_posts").join("2024").join("a.md");
+        assert_eq!(Path::new(&published.new_path), post);
+        assert_eq!(published.date.as_deref(), Some("2024-03-05 08:09:07"));
+        assert!(!draft.exists());
+        assert!(post.parent().unwrap().join("a").join("img.png").is_file());
+        let text = fs::read_to_string(&post).unwrap();
+        assert!(text.contains("date: 2024-03-05 08:09:07"));
+        assert!(!text.contains("publish_at"));
+
+        let unpublished = unpublish(&p.history, &p.scope, &p.store, &p.root, &post).unwrap();
+        assert_eq!(Path::new(&unpublished.new_path), draft);
+        assert_eq!(unpublished.date, None);
+        assert!(!post.exists());
+        assert!(drafts_dir.join("a").join("img.png").is_file());
+        assert!(!post.parent().unwrap().join("a").exists());
+        assert!(fs::read_to_string(&draft).unwrap().contains("date: 2024-03-05 08:09:07"));
+    }
+}
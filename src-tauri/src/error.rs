@@ -0,0 +1,75 @@
+// 文件类命令的统一错误类型
+// 序列化为 { code, message, details? }，前端可以根据 code 区分错误类型
+
+use serde::Serialize;
+
+// 路径不在已注册的项目目录或应用数据目录内
+pub const OUT_OF_SCOPE: &str = "OUT_OF_SCOPE";
+pub const NOT_FOUND: &str = "NOT_FOUND";
+pub const PERMISSION_DENIED: &str = "PERMISSION_DENIED";
+pub const INVALID_PATH: &str = "INVALID_PATH";
+// 文件在读取之后已被外部修改（write_file 的 expected_hash 不匹配）
+pub const CONFLICT: &str = "CONFLICT";
+pub const ALREADY_EXISTS: &str = "ALREADY_EXISTS";
+pub const UNSUPPORTED_ENCODING: &str = "UNSUPPORTED_ENCODING";
+// front-matter 无法解析，details 中可能带有 { line, column }
+pub const INVALID_FRONT_MATTER: &str = "INVALID_FRONT_MATTER";
+// 参数取值不合法（如不支持的排序字段）
+pub const INVALID_ARGUMENT: &str = "INVALID_ARGUMENT";
+pub const DATABASE_ERROR: &str = "DATABASE_ERROR";
+pub const IO_ERROR: &str = "IO_ERROR";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandError {
+    pub code: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl CommandError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        CommandError {
+            code: code.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}] {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<std::io::Error> for CommandError {
+    fn from(e: std::io::Error) -> Self {
+        let code = match e.kind() {
+            std::io::ErrorKind::NotFound => NOT_FOUND,
+            std::io::ErrorKind::PermissionDenied => PERMISSION_DENIED,
+            _ => IO_ERROR,
+        };
+        CommandError::new(code, e.to_string())
+    }
+}
+
+impl From<rusqlite::Error> for CommandError {
+    fn from(e: rusqlite::Error) -> Self {
+        CommandError::new(DATABASE_ERROR, e.to_string())
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::new(IO_ERROR, message)
+    }
+}
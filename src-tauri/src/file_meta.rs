@@ -0,0 +1,97 @@
+// 文件内容指纹
+// read_file 返回内容哈希和修改时间，write_file 可以据此检测文件是否已被外部修改
+// （git pull、VS Code 或另一个 HexoHub 窗口）
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::{self, CommandError};
+use crate::format_system_time;
+use crate::text_encoding::{LineEnding, TextEncoding};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContent {
+    // 已转为 UTF-8、去除 BOM，换行符统一为 \n
+    pub content: String,
+    // 文件原始字节的 SHA-256（十六进制）
+    pub hash: String,
+    pub modified_time: String,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+pub fn modified_time(path: &Path) -> String {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(format_system_time)
+        .unwrap_or_else(|_| "0".to_string())
+}
+
+// 串行化文件保存的“检查 - 快照 - 写入”过程，避免两次保存交错
+pub fn write_lock() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::new(());
+    LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// 读取文件的原始字节，文件不存在时返回 None
+pub fn read_if_exists(path: &Path) -> Result<Option<Vec<u8>>, CommandError> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// 检查磁盘上的文件（current 为刚读取的内容）是否仍然是调用方读取时的版本
+// 不一致时返回 CONFLICT 错误，details 中附带当前磁盘内容，供前端合并或提示
+pub fn check_expected_hash(path: &Path, current: Option<&[u8]>, expected_hash: &str) -> Result<(), CommandError> {
+    let current_hash = current.map(content_hash);
+    if current_hash.as_deref() == Some(expected_hash) {
+        return Ok(());
+    }
+
+    let message = if current.is_some() {
+        format!("文件已被其他程序修改: {}", path.display())
+    } else {
+        format!("文件已被其他程序删除: {}", path.display())
+    };
+    let current_content = current
+        .and_then(|bytes| crate::text_encoding::decode(bytes).ok())
+        .map(|decoded| decoded.content);
+    Err(CommandError::new(error::CONFLICT, message).with_details(serde_json::json!({
+        "currentContent": current_content,
+        "currentHash": current_hash,
+        "modifiedTime": current.map(|_| modified_time(path)),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_hash_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("post.md");
+        assert!(check_expected_hash(&path, Some(b"abc"), &content_hash(b"abc")).is_ok());
+
+        let e = check_expected_hash(&path, Some(b"changed"), &content_hash(b"abc")).unwrap_err();
+        assert_eq!(e.code, error::CONFLICT);
+        let details = e.details.unwrap();
+        assert_eq!(details["currentContent"], "changed");
+        assert_eq!(details["currentHash"], content_hash(b"changed"));
+
+        let e = check_expected_hash(&path, None, &content_hash(b"abc")).unwrap_err();
+        assert_eq!(e.code, error::CONFLICT);
+        assert!(e.details.unwrap()["currentContent"].is_null());
+    }
+}
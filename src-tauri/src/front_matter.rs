@@ -0,0 +1,726 @@
+// 文章 front-matter 解析与修改
+// 支持 Hexo 的三种写法：--- YAML ---、;;; JSON ;;;（内容不含外层花括号）和 +++ TOML +++，
+// 以及省略开头分隔符的 "title: xxx\n---" 形式。
+// 修改时只改写发生变化的顶层键，未知键、键顺序和注释保持原样
+
+use std::collections::HashMap;
+use std::fs;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::State;
+
+use crate::atomic_write::WriteOptions;
+use crate::content_store::ContentStore;
+use crate::error::{self, CommandError};
+use crate::file_meta;
+use crate::history::VersionHistory;
+use crate::save_file;
+use crate::scope::PathScope;
+use crate::text_encoding::{self, LineEnding, TextEncoding};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterFormat {
+    Yaml,
+    Json,
+    Toml,
+    // 文件没有 front-matter
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct FrontMatter {
+    pub format: FrontMatterFormat,
+    pub data: Map<String, Value>,
+    // 分隔符之间的原始文本
+    pub raw: String,
+    pub body: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedPost {
+    pub format: FrontMatterFormat,
+    pub front_matter: Map<String, Value>,
+    pub raw_front_matter: String,
+    pub body: String,
+    pub hash: String,
+    pub modified_time: String,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+struct Split<'a> {
+    format: FrontMatterFormat,
+    separator: &'a str,
+    // 是否以分隔符开头
+    leading: bool,
+    raw: &'a str,
+    body: &'a str,
+}
+
+// 由三个以上相同字符组成的分隔符行
+fn separator_format(line: &str) -> FrontMatterFormat {
+    let line = line.trim_end();
+    let Some(first) = line.chars().next() else {
+        return FrontMatterFormat::None;
+    };
+    if line.len() < 3 || !line.chars().all(|c| c == first) {
+        return FrontMatterFormat::None;
+    }
+    match first {
+        '-' => FrontMatterFormat::Yaml,
+        ';' => FrontMatterFormat::Json,
+        '+' => FrontMatterFormat::Toml,
+        _ => FrontMatterFormat::None,
+    }
+}
+
+// 拆分 front-matter 和正文（text 的换行符已统一为 \n）
+fn split(text: &str) -> Option<Split<'_>> {
+    let first_line = text.split('\n').next().unwrap_or("");
+    let format = separator_format(first_line);
+    if format != FrontMatterFormat::None {
+        let start = first_line.len() + 1;
+        if start > text.len() {
+            return None;
+        }
+        let separator = first_line.trim_end();
+        let mut offset = start;
+        for line in text[start..].split_inclusive('\n') {
+            if line.trim_end() == separator {
+                let raw = &text[start..offset];
+                return Some(Split {
+                    format,
+                    separator,
+                    leading: true,
+                    raw: raw.strip_suffix('\n').unwrap_or(raw),
+                    body: &text[offset + line.len()..],
+                });
+            }
+            offset += line.len();
+        }
+        return None;
+    }
+
+    // 省略开头分隔符的写法（Hexo 只对 YAML 和 JSON 支持）
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let format = separator_format(line);
+        if matches!(format, FrontMatterFormat::Yaml | FrontMatterFormat::Json) {
+            if offset == 0 {
+                return None;
+            }
+            return Some(Split {
+                format,
+                separator: line.trim_end(),
+                leading: false,
+                raw: &text[..offset - 1],
+                body: &text[offset + line.len()..],
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn invalid_front_matter(message: impl Into<String>) -> CommandError {
+    CommandError::new(error::INVALID_FRONT_MATTER, message)
+}
+
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| (yaml_key_to_string(key), yaml_to_json(value)))
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn yaml_key_to_string(key: serde_yaml::Value) -> String {
+    match key {
+        serde_yaml::Value::String(s) => s,
+        other => serde_yaml::to_string(&other)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        // 日期时间转为字符串，与 YAML 中的写法保持一致
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(items) => Value::Array(items.iter().map(toml_value_to_json).collect()),
+        toml_edit::Value::InlineTable(table) => Value::Object(
+            table
+                .iter()
+                .map(|(key, value)| (key.to_string(), toml_value_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+fn toml_item_to_json(item: &toml_edit::Item) -> Value {
+    match item {
+        toml_edit::Item::None => Value::Null,
+        toml_edit::Item::Value(value) => toml_value_to_json(value),
+        toml_edit::Item::Table(table) => Value::Object(
+            table
+                .iter()
+                .map(|(key, item)| (key.to_string(), toml_item_to_json(item)))
+                .collect(),
+        ),
+        toml_edit::Item::ArrayOfTables(tables) => Value::Array(
+            tables
+                .iter()
+                .map(|table| toml_item_to_json(&toml_edit::Item::Table(table.clone())))
+                .collect(),
+        ),
+    }
+}
+
+// TOML 没有 null，值为 null 时返回 None（删除该键）
+fn json_to_toml(value: &Value) -> Option<toml_edit::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => toml_edit::Value::from(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => toml_edit::Value::from(i),
+            None => toml_edit::Value::from(n.as_f64().unwrap_or(0.0)),
+        },
+        Value::String(s) => toml_edit::Value::from(s.as_str()),
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items.iter().filter_map(json_to_toml) {
+                array.push(item);
+            }
+            toml_edit::Value::Array(array)
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                if let Some(item) = json_to_toml(item) {
+                    table.insert(key, item);
+                }
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
+
+fn parse_raw(format: FrontMatterFormat, raw: &str, line_offset: usize) -> Result<Map<String, Value>, CommandError> {
+    match format {
+        FrontMatterFormat::Yaml => {
+            if raw.trim().is_empty() {
+                return Ok(Map::new());
+            }
+            let value: serde_yaml::Value = serde_yaml::from_str(raw).map_err(|e| {
+                let error = invalid_front_matter(format!("YAML front-matter 解析失败: {}", e));
+                match e.location() {
+                    Some(location) => error.with_details(serde_json::json!({
+                        "line": location.line() + line_offset,
+                        "column": location.column(),
+                    })),
+                    None => error,
+                }
+            })?;
+            match yaml_to_json(value) {
+                Value::Object(map) => Ok(map),
+                Value::Null => Ok(Map::new()),
+                _ => Err(invalid_front_matter("front-matter 必须是键值对")),
+            }
+        }
+        FrontMatterFormat::Json => serde_json::from_str(&format!("{{{}}}", raw)).map_err(|e| {
+            invalid_front_matter(format!("JSON front-matter 解析失败: {}", e)).with_details(serde_json::json!({
+                "line": e.line() + line_offset,
+                "column": e.column(),
+            }))
+        }),
+        FrontMatterFormat::Toml => {
+            let document: toml_edit::DocumentMut = raw
+                .parse()
+                .map_err(|e| invalid_front_matter(format!("TOML front-matter 解析失败: {}", e)))?;
+            Ok(document
+                .iter()
+                .map(|(key, item)| (key.to_string(), toml_item_to_json(item)))
+                .collect())
+        }
+        FrontMatterFormat::None => Ok(Map::new()),
+    }
+}
+
+// 解析文章文本（换行符已统一为 \n）
+pub fn parse_front_matter(text: &str) -> Result<FrontMatter, CommandError> {
+    let no_front_matter = || FrontMatter {
+        format: FrontMatterFormat::None,
+        data: Map::new(),
+        raw: String::new(),
+        body: text.to_string(),
+    };
+    let Some(split) = split(text) else {
+        return Ok(no_front_matter());
+    };
+
+    let line_offset = if split.leading { 1 } else { 0 };
+    let data = match parse_raw(split.format, split.raw, line_offset) {
+        Ok(data) => data,
+        // 省略开头分隔符时，第一段内容不一定是 front-matter（可能只是正文中的分隔线）
+        Err(_) if !split.leading => return Ok(no_front_matter()),
+        Err(e) => return Err(e),
+    };
+    if !split.leading && data.is_empty() {
+        return Ok(no_front_matter());
+    }
+
+    Ok(FrontMatter {
+        format: split.format,
+        data,
+        raw: split.raw.to_string(),
+        body: split.body.to_string(),
+    })
+}
+
+struct YamlEntry {
+    key: String,
+    // 行号范围 [start, end)
+    start: usize,
+    end: usize,
+}
+
+fn yaml_key_pattern() -> Regex {
+    Regex::new(r#"^("(?:[^"\\]|\\.)*"|'(?:[^']|'')*'|[^\s#'"\-][^:]*?|-[^\s:][^:]*?)\s*:(?:\s|$)"#).unwrap()
+}
+
+// 找出所有顶层键及其占用的行（值为多行列表或映射时包含后续缩进行）
+fn yaml_entries(lines: &[&str], key_pattern: &Regex) -> Vec<YamlEntry> {
+    let mut entries: Vec<YamlEntry> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let is_continuation =
+            line.is_empty() || line.starts_with([' ', '\t']) || line.starts_with("- ") || *line == "-";
+        if is_continuation {
+            continue;
+        }
+        if let Some(last) = entries.last_mut() {
+            if last.end == usize::MAX {
+                last.end = index;
+            }
+        }
+        if let Some(caps) = key_pattern.captures(line) {
+            let key = caps[1].to_string();
+            let key = match (key.strip_prefix('"'), key.strip_prefix('\'')) {
+                (Some(quoted), _) => serde_json::from_str::<String>(&key).unwrap_or_else(|_| quoted.trim_end_matches('"').to_string()),
+                (_, Some(quoted)) => quoted.trim_end_matches('\'').replace("''", "'"),
+                _ => key.trim_end().to_string(),
+            };
+            entries.push(YamlEntry {
+                key,
+                start: index,
+                end: usize::MAX,
+            });
+        }
+    }
+    if let Some(last) = entries.last_mut() {
+        if last.end == usize::MAX {
+            last.end = lines.len();
+        }
+    }
+    // 键之间的空行不属于前一个键
+    for entry in &mut entries {
+        while entry.end > entry.start + 1 && lines[entry.end - 1].trim().is_empty() {
+            entry.end -= 1;
+        }
+    }
+    entries
+}
+
+// 列表项的缩进（"tags:\n  - a" 中的两个空格），用于保持原文件的缩进风格
+fn list_indent(lines: &[&str], entry: &YamlEntry) -> Option<String> {
+    lines[entry.start + 1..entry.end].iter().find_map(|line| {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('-') {
+            Some(line[..line.len() - trimmed.len()].to_string())
+        } else {
+            None
+        }
+    })
+}
+
+fn is_flow_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+fn render_flow_item(value: &Value) -> String {
+    let plain = serde_yaml::to_string(value)
+        .map(|s| s.trim_end().to_string())
+        .unwrap_or_default();
+    // 流式列表中逗号、括号等有特殊含义，此时改用双引号（JSON 字符串也是合法的 YAML）
+    if plain.contains([',', '[', ']', '{', '}', '#']) || plain.contains(": ") {
+        serde_json::to_string(value).unwrap_or(plain)
+    } else {
+        plain
+    }
+}
+
+// 生成一个顶层键的 YAML 文本；original 为原来的行（保持行内列表和缩进风格）
+fn render_yaml_entry(key: &str, value: &Value, original: Option<&[&str]>, indent: &str) -> Result<String, CommandError> {
+    if let (Some([line]), Value::Array(items)) = (original, value) {
+        if let Some((key_text, rest)) = line.split_once(':') {
+            if rest.trim_start().starts_with('[') && items.iter().all(is_flow_scalar) {
+                let items: Vec<String> = items.iter().map(render_flow_item).collect();
+                return Ok(format!("{}: [{}]", key_text, items.join(", ")));
+            }
+        }
+    }
+
+    let mut mapping = Map::new();
+    mapping.insert(key.to_string(), value.clone());
+    let rendered = serde_yaml::to_string(&Value::Object(mapping))
+        .map_err(|e| invalid_front_matter(format!("无法序列化 {}: {}", key, e)))?;
+    let mut lines = rendered.trim_end().lines();
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        result.push_str(indent);
+        result.push_str(line);
+    }
+    Ok(result)
+}
+
+fn update_yaml(raw: &str, updates: &Map<String, Value>, remove_keys: &[String], current: &Map<String, Value>) -> Result<String, CommandError> {
+    let lines: Vec<&str> = if raw.is_empty() { Vec::new() } else { raw.split('\n').collect() };
+    let entries = yaml_entries(&lines, &yaml_key_pattern());
+    let default_indent = entries
+        .iter()
+        .find_map(|entry| list_indent(&lines, entry))
+        .unwrap_or_default();
+
+    // 起始行号 -> 替换文本（None 表示删除）
+    let mut replacements: HashMap<usize, (usize, Option<String>)> = HashMap::new();
+    let mut appended: Vec<String> = Vec::new();
+    for (key, value) in updates {
+        if current.get(key) == Some(value) {
+            continue;
+        }
+        match entries.iter().find(|entry| &entry.key == key) {
+            Some(entry) => {
+                let original = &lines[entry.start..entry.end];
+                let indent = list_indent(&lines, entry).unwrap_or_else(|| default_indent.clone());
+                let rendered = render_yaml_entry(key, value, Some(original), &indent)?;
+                replacements.insert(entry.start, (entry.end, Some(rendered)));
+            }
+            None => appended.push(render_yaml_entry(key, value, None, &default_indent)?),
+        }
+    }
+    for key in remove_keys {
+        if updates.contains_key(key) {
+            continue;
+        }
+        if let Some(entry) = entries.iter().find(|entry| &entry.key == key) {
+            replacements.insert(entry.start, (entry.end, None));
+        }
+    }
+
+    let mut output: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        match replacements.get(&index) {
+            Some((end, replacement)) => {
+                output.extend(replacement.clone());
+                index = *end;
+            }
+            None => {
+                output.push(lines[index].to_string());
+                index += 1;
+            }
+        }
+    }
+    // 新增的键追加到末尾（在结尾空行之前）
+    let trailing_blank = output.iter().rev().take_while(|line| line.trim().is_empty()).count();
+    let insert_at = output.len() - trailing_blank;
+    output.splice(insert_at..insert_at, appended);
+    Ok(output.join("\n"))
+}
+
+fn update_json(updates: &Map<String, Value>, remove_keys: &[String], current: &Map<String, Value>) -> Result<String, CommandError> {
+    let mut data = current.clone();
+    for (key, value) in updates {
+        data.insert(key.clone(), value.clone());
+    }
+    for key in remove_keys.iter().filter(|key| !updates.contains_key(*key)) {
+        data.shift_remove(key);
+    }
+    if data.is_empty() {
+        return Ok(String::new());
+    }
+
+    // 去掉外层花括号并取消一级缩进，与 Hexo 的 ;;; 写法一致
+    let pretty = serde_json::to_string_pretty(&data).map_err(|e| invalid_front_matter(e.to_string()))?;
+    let lines: Vec<&str> = pretty.lines().collect();
+    Ok(lines[1..lines.len() - 1]
+        .iter()
+        .map(|line| line.strip_prefix("  ").unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn update_toml(raw: &str, updates: &Map<String, Value>, remove_keys: &[String]) -> Result<String, CommandError> {
+    let mut document: toml_edit::DocumentMut = raw
+        .parse()
+        .map_err(|e| invalid_front_matter(format!("TOML front-matter 解析失败: {}", e)))?;
+    for (key, value) in updates {
+        match json_to_toml(value) {
+            Some(value) => document[key.as_str()] = toml_edit::Item::Value(value),
+            None => {
+                document.remove(key);
+            }
+        }
+    }
+    for key in remove_keys.iter().filter(|key| !updates.contains_key(*key)) {
+        document.remove(key);
+    }
+    Ok(document.to_string().trim_end_matches('\n').to_string())
+}
+
+// 修改 front-matter 中的若干顶层键，返回新的文章文本；没有 front-matter 时新建 YAML front-matter
+pub fn update_front_matter_text(text: &str, updates: &Map<String, Value>, remove_keys: &[String]) -> Result<String, CommandError> {
+    let parsed = parse_front_matter(text)?;
+    let apply = |mut data: Map<String, Value>| {
+        for (key, value) in updates {
+            data.insert(key.clone(), value.clone());
+        }
+        for key in remove_keys.iter().filter(|key| !updates.contains_key(*key)) {
+            data.shift_remove(key);
+        }
+        data
+    };
+    // 没有实际变化时原样返回，避免无意义的格式改动
+    if parsed.format != FrontMatterFormat::None && apply(parsed.data.clone()) == parsed.data {
+        return Ok(text.to_string());
+    }
+
+    let (separator, leading) = match split(text).filter(|_| parsed.format != FrontMatterFormat::None) {
+        Some(split) => (split.separator.to_string(), split.leading),
+        None => ("---".to_string(), true),
+    };
+
+    let raw = match parsed.format {
+        FrontMatterFormat::Yaml | FrontMatterFormat::None => update_yaml(&parsed.raw, updates, remove_keys, &parsed.data)?,
+        FrontMatterFormat::Json => update_json(updates, remove_keys, &parsed.data)?,
+        FrontMatterFormat::Toml => update_toml(&parsed.raw, updates, remove_keys)?,
+    };
+
+    // 校验改写结果：重新解析后应与预期的数据一致。
+    // 末尾的 YAML 块标量（|）之后追加键时会多出结尾换行，因此改写前后都在末尾补一个换行再比较
+    let format = if parsed.format == FrontMatterFormat::None { FrontMatterFormat::Yaml } else { parsed.format };
+    let (mut expected, actual) = match format {
+        FrontMatterFormat::Yaml => (
+            apply(parse_raw(format, &format!("{}\n", parsed.raw), 0)?),
+            parse_raw(format, &format!("{}\n", raw), 0)?,
+        ),
+        _ => (apply(parsed.data.clone()), parse_raw(format, &raw, 0)?),
+    };
+    // TOML 不能表示 null，null 值的键已被删除
+    if format == FrontMatterFormat::Toml {
+        expected.retain(|_, value| !value.is_null());
+    }
+    if actual != expected {
+        return Err(invalid_front_matter("front-matter 改写结果校验失败，文件未修改"));
+    }
+
+    Ok(match (leading, raw.is_empty()) {
+        (_, true) => format!("{0}\n{0}\n{1}", separator, parsed.body),
+        (true, false) => format!("{0}\n{1}\n{0}\n{2}", separator, raw, parsed.body),
+        (false, false) => format!("{}\n{}\n{}", raw, separator, parsed.body),
+    })
+}
+
+fn to_parsed_post(text: &str, bytes: &[u8], path: &std::path::Path, encoding: TextEncoding, line_ending: LineEnding) -> Result<ParsedPost, CommandError> {
+    let front_matter = parse_front_matter(text)?;
+    Ok(ParsedPost {
+        format: front_matter.format,
+        front_matter: front_matter.data,
+        raw_front_matter: front_matter.raw,
+        body: front_matter.body,
+        hash: file_meta::content_hash(bytes),
+        modified_time: file_meta::modified_time(path),
+        encoding,
+        line_ending,
+    })
+}
+
+// 读取文章并解析 front-matter
+#[tauri::command]
+pub async fn parse_post(file_path: String, scope: State<'_, PathScope>) -> Result<ParsedPost, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let bytes = fs::read(&path)?;
+    let decoded = text_encoding::decode(&bytes)?;
+    to_parsed_post(&decoded.content, &bytes, &path, decoded.encoding, decoded.line_ending)
+}
+
+// 修改文章的 front-matter：updates 中的键被设置（不存在则追加），remove_keys 中的键被删除
+#[tauri::command]
+pub async fn update_front_matter(
+    file_path: String,
+    updates: Map<String, Value>,
+    remove_keys: Option<Vec<String>>,
+    expected_hash: Option<String>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<ParsedPost, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let _guard = file_meta::write_lock();
+    let previous = fs::read(&path)?;
+    if let Some(expected_hash) = &expected_hash {
+        file_meta::check_expected_hash(&path, Some(&previous), expected_hash)?;
+    }
+
+    let decoded = text_encoding::decode(&previous)?;
+    let text = update_front_matter_text(&decoded.content, &updates, &remove_keys.unwrap_or_default())?;
+    let bytes = text_encoding::encode(&text, decoded.encoding, decoded.line_ending);
+
+    if bytes != previous {
+        save_file(&history, &scope, &store, &path, Some(&previous), &bytes, WriteOptions::default())?;
+    }
+    to_parsed_post(&text, &bytes, &path, decoded.encoding, decoded.line_ending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn updates(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn splits_each_format() {
+        let yaml = split("---\ntitle: a\n---\nbody\n").unwrap();
+        assert_eq!((yaml.format, yaml.leading, yaml.raw, yaml.body), (FrontMatterFormat::Yaml, true, "title: a", "body\n"));
+
+        let json = split(";;;\n\"title\": \"a\"\n;;;\nbody").unwrap();
+        assert_eq!((json.format, json.raw, json.body), (FrontMatterFormat::Json, "\"title\": \"a\"", "body"));
+
+        let toml = split("+++\ntitle = \"a\"\n+++\nbody").unwrap();
+        assert_eq!((toml.format, toml.raw, toml.body), (FrontMatterFormat::Toml, "title = \"a\"", "body"));
+
+        let bare = split("title: a\n---\nbody").unwrap();
+        assert_eq!((bare.format, bare.leading, bare.raw, bare.body), (FrontMatterFormat::Yaml, false, "title: a", "body"));
+
+        assert!(split("---\ntitle: a\n").is_none());
+    }
+
+    #[test]
+    fn thematic_break_in_body_is_not_front_matter() {
+        let text = "# Hello\n\n---\n\nbody";
+        let parsed = parse_front_matter(text).unwrap();
+        assert_eq!(parsed.format, FrontMatterFormat::None);
+        assert_eq!(parsed.body, text);
+    }
+
+    #[test]
+    fn parse_raw_reports_file_line_numbers() {
+        let error = parse_front_matter(";;;\n\"a\": 1,\n\"b\": \n;;;\n").unwrap_err();
+        assert_eq!(error.code, error::INVALID_FRONT_MATTER);
+        assert_eq!(error.details.unwrap()["line"], 3);
+
+        let error = parse_front_matter("---\ntitle: a\ntags: [x\n---\n").unwrap_err();
+        assert_eq!(error.code, error::INVALID_FRONT_MATTER);
+        assert!(error.details.is_some());
+    }
+
+    #[test]
+    fn yaml_update_keeps_comments_order_and_style() {
+        let text = "---\ntitle: Old\n# 分类\ndate: 2024-01-01 10:00:00\ntags: [a, b]\ncategories:\n    - x\n    - y\ndescription: |\n  line 1\n  line 2\n---\nbody\n";
+        let result = update_front_matter_text(
+            text,
+            &updates(json!({ "title": "New", "tags": ["a", "b", "c, d"], "categories": ["x", "z"], "updated": "2024-02-01" })),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "---\ntitle: New\n# 分类\ndate: 2024-01-01 10:00:00\ntags: [a, b, \"c, d\"]\ncategories:\n    - x\n    - z\ndescription: |\n  line 1\n  line 2\nupdated: 2024-02-01\n---\nbody\n"
+        );
+    }
+
+    #[test]
+    fn yaml_update_removes_multi_line_entries() {
+        let text = "---\ntitle: a\ncategories:\n  - x\n  - y\ndraft: true\n---\nbody";
+        let result = update_front_matter_text(text, &Map::new(), &["categories".to_string()]).unwrap();
+        assert_eq!(result, "---\ntitle: a\ndraft: true\n---\nbody");
+    }
+
+    #[test]
+    fn unchanged_update_returns_text_as_is() {
+        let text = "---\ntitle:   a   # note\n---\nbody";
+        assert_eq!(update_front_matter_text(text, &updates(json!({ "title": "a" })), &[]).unwrap(), text);
+    }
+
+    #[test]
+    fn json_update_keeps_key_order() {
+        let text = ";;;\n\"title\": \"a\",\n\"tags\": [\"x\"]\n;;;\nbody";
+        let result = update_front_matter_text(text, &updates(json!({ "title": "b" })), &[]).unwrap();
+        assert_eq!(result, ";;;\n\"title\": \"b\",\n\"tags\": [\n  \"x\"\n]\n;;;\nbody");
+    }
+
+    #[test]
+    fn toml_update_keeps_comments() {
+        let text = "+++\n# 标题\ntitle = \"a\"\ndraft = true\n+++\nbody";
+        let result = update_front_matter_text(text, &updates(json!({ "title": "b" })), &["draft".to_string()]).unwrap();
+        assert_eq!(result, "+++\n# 标题\ntitle = \"b\"\n+++\nbody");
+    }
+
+    #[test]
+    fn updates_without_leading_separator() {
+        let result = update_front_matter_text("title: a\n---\nbody", &updates(json!({ "title": "b" })), &[]).unwrap();
+        assert_eq!(result, "title: b\n---\nbody");
+    }
+
+    #[test]
+    fn adds_front_matter_when_missing() {
+        let result = update_front_matter_text("body\n", &updates(json!({ "title": "a" })), &[]).unwrap();
+        assert_eq!(result, "---\ntitle: a\n---\nbody\n");
+    }
+
+    #[test]
+    fn round_trips_crlf_and_bom() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(b"---\r\ntitle: a\r\ntags:\r\n  - x\r\n---\r\nbody\r\n");
+        let decoded = text_encoding::decode(&bytes).unwrap();
+        let text = update_front_matter_text(&decoded.content, &updates(json!({ "title": "b" })), &[]).unwrap();
+        let encoded = text_encoding::encode(&text, decoded.encoding, decoded.line_ending);
+
+        let mut expected = vec![0xEF, 0xBB, 0xBF];
+        expected.extend_from_slice(b"---\r\ntitle: b\r\ntags:\r\n  - x\r\n---\r\nbody\r\n");
+        assert_eq!(encoded, expected);
+    }
+}
@@ -0,0 +1,531 @@
+// 本地版本历史
+// write_file 覆盖 source/ 下的 markdown 前，把旧内容 gzip 压缩后存入应用数据目录，
+// 不使用 git 的用户也能找回被误覆盖的文章。目录结构：
+//   history/retention.json                   保留策略
+//   history/<项目哈希>/index.json            各文件的版本列表
+//   history/<项目哈希>/objects/<内容哈希>.gz  版本内容（相同内容只存一份）
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use tauri::State;
+
+use crate::atomic_write::{self, WriteOptions};
+use crate::content_store::ContentStore;
+use crate::error::{self, CommandError};
+use crate::file_meta::{self, FileContent};
+use crate::post_move::is_markdown;
+use crate::save_file;
+use crate::scope::PathScope;
+use crate::text_encoding;
+
+const INDEX_FILE: &str = "index.json";
+const RETENTION_FILE: &str = "retention.json";
+const OBJECTS_DIR: &str = "objects";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRetention {
+    // 每个文件最多保留的版本数，0 表示不限
+    pub max_versions: usize,
+    // 超过天数的版本被清理，不设置则不按时间清理
+    pub max_age_days: Option<u64>,
+}
+
+impl Default for HistoryRetention {
+    fn default() -> Self {
+        HistoryRetention {
+            max_versions: 50,
+            max_age_days: Some(90),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub id: String,
+    // 保存时间（毫秒时间戳字符串，与 FileInfo.modified_time 一致）
+    pub saved_time: String,
+    pub hash: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryIndex {
+    project_root: String,
+    // 键为相对于项目根目录的路径（使用 / 分隔），版本按时间从旧到新排列
+    files: BTreeMap<String, Vec<VersionInfo>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    // "equal" | "insert" | "delete"
+    pub kind: &'static str,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionDiff {
+    pub lines: Vec<DiffLine>,
+    pub insertions: usize,
+    pub deletions: usize,
+    // 统一 diff 格式文本，方便直接展示或复制
+    pub unified: String,
+}
+
+pub struct VersionHistory {
+    base_dir: PathBuf,
+    // 串行化索引的读改写
+    lock: Mutex<()>,
+    retention: Mutex<HistoryRetention>,
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+// 读取 JSON 文件，文件不存在时返回 None。
+// 内容无法解析时把文件改名为 <文件名>.corrupt-<毫秒时间戳> 保留下来并返回错误，
+// 避免调用方当作空数据继续写入而覆盖掉原有记录
+pub(crate) fn read_json_if_exists<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, CommandError> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let corrupt_path = path.with_file_name(format!("{}.corrupt-{}", file_name, now_millis()));
+            fs::rename(path, &corrupt_path)?;
+            Err(CommandError::new(
+                error::IO_ERROR,
+                format!("{} 已损坏（{}），已另存为 {}", path.display(), e, corrupt_path.display()),
+            )
+            .with_details(serde_json::json!({ "corruptPath": corrupt_path.to_string_lossy() })))
+        }
+    }
+}
+
+// 读取设置类 JSON 文件，文件不存在时返回默认值；损坏的文件同样改名保留，记录日志后返回默认值
+pub(crate) fn read_json<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> T {
+    read_json_if_exists(path)
+        .unwrap_or_else(|e| {
+            log::warn!("读取 {} 失败，使用默认值: {}", path.display(), e);
+            None
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), CommandError> {
+    let json = serde_json::to_vec_pretty(value).map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write::write_atomic(path, &json, WriteOptions::default())?;
+    Ok(())
+}
+
+fn compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+fn decompress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+impl VersionHistory {
+    pub fn new(base_dir: PathBuf) -> Self {
+        let retention = read_json(&base_dir.join(RETENTION_FILE));
+        VersionHistory {
+            base_dir,
+            lock: Mutex::new(()),
+            retention: Mutex::new(retention),
+        }
+    }
+
+    fn project_dir(&self, project_root: &Path) -> PathBuf {
+        let key = file_meta::content_hash(project_root.to_string_lossy().as_bytes());
+        self.base_dir.join(&key[..16])
+    }
+
+    // 定位文件所属项目及其相对路径；只有 source/ 下的 markdown 纳入历史
+    fn locate(&self, scope: &PathScope, path: &Path) -> Option<(PathBuf, String)> {
+        if !is_markdown(path) {
+            return None;
+        }
+        let root = scope.root_of(path)?;
+        let relative = path.strip_prefix(&root).ok()?;
+        if !relative.starts_with("source") {
+            return None;
+        }
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        Some((root, relative))
+    }
+
+    fn locate_or_err(&self, scope: &PathScope, path: &Path) -> Result<(PathBuf, String), CommandError> {
+        self.locate(scope, path).ok_or_else(|| {
+            CommandError::new(
+                error::INVALID_PATH,
+                format!("只有项目 source 目录下的 markdown 文件有版本历史: {}", path.display()),
+            )
+        })
+    }
+
+    // 保存一个版本（与最新版本内容相同时跳过），然后按保留策略清理
+    fn save_version(&self, project_root: &Path, relative: &str, bytes: &[u8]) -> Result<(), CommandError> {
+        let _guard = self.lock.lock().unwrap();
+        let project_dir = self.project_dir(project_root);
+        let index_path = project_dir.join(INDEX_FILE);
+        // 索引无法解析时本次保存失败；索引原本不存在时不清理内容文件（另见 remove_unused_objects）
+        let existing: Option<HistoryIndex> = read_json_if_exists(&index_path)?;
+        let collect_garbage = existing.is_some();
+        let mut index = existing.unwrap_or_default();
+        index.project_root = project_root.to_string_lossy().to_string();
+
+        let hash = file_meta::content_hash(bytes);
+        let versions = index.files.entry(relative.to_string()).or_default();
+        if versions.last().is_some_and(|latest| latest.hash == hash) {
+            return Ok(());
+        }
+
+        let object_path = project_dir.join(OBJECTS_DIR).join(format!("{}.gz", hash));
+        if !object_path.exists() {
+            fs::create_dir_all(project_dir.join(OBJECTS_DIR))?;
+            atomic_write::write_atomic(&object_path, &compress(bytes)?, WriteOptions::default())?;
+        }
+
+        let millis = now_millis();
+        // 同一毫秒内多次保存时追加序号，保证 id 唯一
+        let mut id = millis.to_string();
+        let mut suffix = 1;
+        while versions.iter().any(|v| v.id == id) {
+            id = format!("{}-{}", millis, suffix);
+            suffix += 1;
+        }
+        versions.push(VersionInfo {
+            id,
+            saved_time: millis.to_string(),
+            hash,
+            size: bytes.len() as u64,
+        });
+
+        let retention = self.retention.lock().unwrap().clone();
+        prune_index(&mut index, &retention);
+        write_json(&index_path, &index)?;
+        if collect_garbage {
+            remove_unused_objects(&project_dir, &index);
+        }
+        Ok(())
+    }
+
+    fn versions(&self, project_root: &Path, relative: &str) -> Result<Vec<VersionInfo>, CommandError> {
+        let _guard = self.lock.lock().unwrap();
+        let index: HistoryIndex = read_json_if_exists(&self.project_dir(project_root).join(INDEX_FILE))?.unwrap_or_default();
+        Ok(index.files.get(relative).cloned().unwrap_or_default())
+    }
+
+    fn version_bytes(&self, project_root: &Path, relative: &str, version_id: &str) -> Result<Vec<u8>, CommandError> {
+        let version = self
+            .versions(project_root, relative)?
+            .into_iter()
+            .find(|v| v.id == version_id)
+            .ok_or_else(|| CommandError::new(error::NOT_FOUND, format!("版本不存在: {}", version_id)))?;
+        let object_path = self
+            .project_dir(project_root)
+            .join(OBJECTS_DIR)
+            .join(format!("{}.gz", version.hash));
+        Ok(decompress(&fs::read(object_path)?)?)
+    }
+}
+
+// 按保留策略裁剪版本列表；每个文件至少保留最新的一个版本
+fn prune_index(index: &mut HistoryIndex, retention: &HistoryRetention) {
+    let cutoff = retention
+        .max_age_days
+        .map(|days| now_millis().saturating_sub(Duration::from_secs(days * 24 * 60 * 60).as_millis()));
+
+    for versions in index.files.values_mut() {
+        if retention.max_versions > 0 && versions.len() > retention.max_versions {
+            versions.drain(..versions.len() - retention.max_versions);
+        }
+        if let Some(cutoff) = cutoff {
+            let latest = versions.pop();
+            versions.retain(|v| v.saved_time.parse::<u128>().unwrap_or(0) >= cutoff);
+            versions.extend(latest);
+        }
+    }
+    index.files.retain(|_, versions| !versions.is_empty());
+}
+
+// 项目目录下是否有损坏后被移走的索引（index.json.corrupt-*）
+fn has_corrupt_index(project_dir: &Path) -> bool {
+    let prefix = format!("{}.corrupt-", INDEX_FILE);
+    fs::read_dir(project_dir).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+    })
+}
+
+// 删除不再被任何版本引用的内容文件。存在损坏的索引时不清理：无法确定其中引用了哪些版本，
+// 内容文件要留给用户恢复，直到损坏的索引被处理掉
+fn remove_unused_objects(project_dir: &Path, index: &HistoryIndex) {
+    if has_corrupt_index(project_dir) {
+        return;
+    }
+    let used: HashSet<String> = index
+        .files
+        .values()
+        .flatten()
+        .map(|v| format!("{}.gz", v.hash))
+        .collect();
+    let Ok(entries) = fs::read_dir(project_dir.join(OBJECTS_DIR)) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !used.contains(entry.file_name().to_string_lossy().as_ref()) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+// write_file 覆盖文件前调用：保存旧内容（不在历史范围内的文件直接忽略）
+pub fn snapshot(history: &VersionHistory, scope: &PathScope, path: &Path, previous: &[u8]) -> Result<(), CommandError> {
+    match history.locate(scope, path) {
+        Some((root, relative)) => history.save_version(&root, &relative, previous),
+        None => Ok(()),
+    }
+}
+
+fn decode_text(bytes: &[u8]) -> Result<String, CommandError> {
+    text_encoding::decode(bytes).map(|decoded| decoded.content)
+}
+
+// 列出文件的历史版本（从新到旧）
+#[tauri::command]
+pub async fn list_versions(
+    file_path: String,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+) -> Result<Vec<VersionInfo>, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let (root, relative) = history.locate_or_err(&scope, &path)?;
+    let mut versions = history.versions(&root, &relative)?;
+    versions.reverse();
+    Ok(versions)
+}
+
+// 比较两个版本；to_version 为空时与磁盘上的当前内容比较
+#[tauri::command]
+pub async fn diff_versions(
+    file_path: String,
+    from_version: String,
+    to_version: Option<String>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+) -> Result<VersionDiff, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let (root, relative) = history.locate_or_err(&scope, &path)?;
+
+    let old_text = decode_text(&history.version_bytes(&root, &relative, &from_version)?)?;
+    let new_text = match &to_version {
+        Some(version_id) => decode_text(&history.version_bytes(&root, &relative, version_id)?)?,
+        None => decode_text(&fs::read(&path)?)?,
+    };
+
+    let diff = TextDiff::from_lines(&old_text, &new_text);
+    let mut lines = Vec::new();
+    let (mut insertions, mut deletions) = (0, 0);
+    for change in diff.iter_all_changes() {
+        let kind = match change.tag() {
+            ChangeTag::Equal => "equal",
+            ChangeTag::Insert => {
+                insertions += 1;
+                "insert"
+            }
+            ChangeTag::Delete => {
+                deletions += 1;
+                "delete"
+            }
+        };
+        lines.push(DiffLine {
+            kind,
+            old_line: change.old_index().map(|i| i + 1),
+            new_line: change.new_index().map(|i| i + 1),
+            text: change.value().trim_end_matches('\n').to_string(),
+        });
+    }
+
+    let unified = diff
+        .unified_diff()
+        .context_radius(3)
+        .header(&from_version, to_version.as_deref().unwrap_or("current"))
+        .to_string();
+    Ok(VersionDiff {
+        lines,
+        insertions,
+        deletions,
+        unified,
+    })
+}
+
+// 恢复到指定版本；恢复前的内容同样会保存为一个版本，恢复操作本身可以撤销。
+// 与 write_file 一样通过 save_file 写入，expected_hash 为编辑器中内容的哈希，磁盘内容不同时返回 CONFLICT
+#[tauri::command]
+pub async fn restore_version(
+    file_path: String,
+    version_id: String,
+    expected_hash: Option<String>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<FileContent, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let (root, relative) = history.locate_or_err(&scope, &path)?;
+    let bytes = history.version_bytes(&root, &relative, &version_id)?;
+
+    let _guard = file_meta::write_lock();
+    let current = file_meta::read_if_exists(&path)?;
+    if let Some(expected_hash) = &expected_hash {
+        file_meta::check_expected_hash(&path, current.as_deref(), expected_hash)?;
+    }
+    save_file(&history, &scope, &store, &path, current.as_deref(), &bytes, WriteOptions::default())?;
+
+    let decoded = text_encoding::decode(&bytes)?;
+    Ok(FileContent {
+        content: decoded.content,
+        hash: file_meta::content_hash(&bytes),
+        modified_time: file_meta::modified_time(&path),
+        encoding: decoded.encoding,
+        line_ending: decoded.line_ending,
+    })
+}
+
+#[tauri::command]
+pub async fn get_history_retention(history: State<'_, VersionHistory>) -> Result<HistoryRetention, CommandError> {
+    Ok(history.retention.lock().unwrap().clone())
+}
+
+// 修改保留策略，并立即对所有项目的历史执行一次清理
+#[tauri::command]
+pub async fn set_history_retention(
+    retention: HistoryRetention,
+    history: State<'_, VersionHistory>,
+) -> Result<HistoryRetention, CommandError> {
+    let _guard = history.lock.lock().unwrap();
+    write_json(&history.base_dir.join(RETENTION_FILE), &retention)?;
+    *history.retention.lock().unwrap() = retention.clone();
+
+    if let Ok(projects) = fs::read_dir(&history.base_dir) {
+        for project_dir in projects.flatten().map(|entry| entry.path()).filter(|p| p.is_dir()) {
+            let index_path = project_dir.join(INDEX_FILE);
+            // 损坏的索引已被移走，跳过该项目，不影响其他项目的清理
+            let mut index: HistoryIndex = match read_json_if_exists(&index_path) {
+                Ok(Some(index)) => index,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::warn!("清理版本历史时跳过 {}: {}", project_dir.display(), e);
+                    continue;
+                }
+            };
+            prune_index(&mut index, &retention);
+            write_json(&index_path, &index)?;
+            remove_unused_objects(&project_dir, &index);
+        }
+    }
+    Ok(retention)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corrupt_files(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.to_string_lossy().contains(".corrupt-"))
+            .collect()
+    }
+
+    #[test]
+    fn saves_and_lists_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = VersionHistory::new(dir.path().to_path_buf());
+        let root = Path::new("/blog");
+        history.save_version(root, "source/_posts/a.md", b"v1").unwrap();
+        history.save_version(root, "source/_posts/a.md", b"v1").unwrap();
+        history.save_version(root, "source/_posts/a.md", b"v2").unwrap();
+
+        let versions = history.versions(root, "source/_posts/a.md").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(history.version_bytes(root, "source/_posts/a.md", &versions[0].id).unwrap(), b"v1");
+    }
+
+    #[test]
+    fn corrupt_index_fails_the_save_and_is_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = VersionHistory::new(dir.path().to_path_buf());
+        let root = Path::new("/blog");
+        history.save_version(root, "source/_posts/a.md", b"v1").unwrap();
+
+        let project_dir = history.project_dir(root);
+        fs::write(project_dir.join(INDEX_FILE), "{ not json").unwrap();
+        assert!(history.save_version(root, "source/_posts/a.md", b"v2").is_err());
+        assert!(!project_dir.join(INDEX_FILE).exists());
+        assert_eq!(corrupt_files(&project_dir).len(), 1);
+
+        // 重新开始记录后，之后的保存和清理也不删除损坏的索引所引用的内容文件
+        let retention = HistoryRetention {
+            max_versions: 1,
+            ..HistoryRetention::default()
+        };
+        *history.retention.lock().unwrap() = retention;
+        history.save_version(root, "source/_posts/b.md", b"other").unwrap();
+        history.save_version(root, "source/_posts/b.md", b"other 2").unwrap();
+        history.save_version(root, "source/_posts/b.md", b"other 3").unwrap();
+        let objects = fs::read_dir(project_dir.join(OBJECTS_DIR)).unwrap().count();
+        assert_eq!(objects, 4);
+        assert_eq!(history.versions(root, "source/_posts/b.md").unwrap().len(), 1);
+
+        // 处理掉损坏的索引后恢复清理
+        for path in corrupt_files(&project_dir) {
+            fs::remove_file(path).unwrap();
+        }
+        history.save_version(root, "source/_posts/b.md", b"other 4").unwrap();
+        let objects = fs::read_dir(project_dir.join(OBJECTS_DIR)).unwrap().count();
+        assert_eq!(objects, 1);
+    }
+
+    #[test]
+    fn missing_json_reads_as_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let value: BTreeMap<String, String> = read_json(&dir.path().join("missing.json"));
+        assert!(value.is_empty());
+    }
+}
@@ -10,6 +10,34 @@ use tauri::Manager;
 
 use serde::{Deserialize, Serialize};
 
+mod atomic_write;
+mod content_store;
+mod diagnostics;
+mod dir_ops;
+mod drafts;
+mod error;
+mod file_meta;
+mod front_matter;
+mod history;
+mod link_check;
+mod post_index;
+mod post_move;
+mod publish_stats;
+mod recycle_bin;
+mod scaffolds;
+mod scheduler;
+mod scope;
+mod search;
+mod slug;
+mod taxonomy;
+mod text_encoding;
+mod walk;
+mod watcher;
+mod word_count;
+
+use error::CommandError;
+use scope::PathScope;
+
 // Windows 平台特定的导入，用于隐藏命令行窗口和处理编码
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -28,6 +56,14 @@ struct CommandResult {
     error: Option<String>,
 }
 
+// Hexo 命令执行结果，附带从输出中解析出的错误诊断
+#[derive(Debug, Serialize, Deserialize)]
+struct HexoCommandResult {
+    #[serde(flatten)]
+    result: CommandResult,
+    diagnostics: Vec<diagnostics::HexoDiagnostic>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ValidationResult {
     valid: bool,
@@ -36,16 +72,16 @@ struct ValidationResult {
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct FileInfo {
-    name: String,
-    path: String,
-    is_directory: bool,
-    size: u64,
-    modified_time: String,
+pub(crate) struct FileInfo {
+    pub(crate) name: String,
+    pub(crate) path: String,
+    pub(crate) is_directory: bool,
+    pub(crate) size: u64,
+    pub(crate) modified_time: String,
 }
 
 // 将 SystemTime 转换为 ISO 8601 格式的字符串
-fn format_system_time(time: SystemTime) -> String {
+pub(crate) fn format_system_time(time: SystemTime) -> String {
     match time.duration_since(SystemTime::UNIX_EPOCH) {
         Ok(duration) => {
             let timestamp_millis = duration.as_millis();
@@ -57,55 +93,198 @@ fn format_system_time(time: SystemTime) -> String {
     }
 }
 
-// 读取文件
+// 读取文件（同时返回内容哈希和修改时间，用于写入时的冲突检测）
 #[tauri::command]
-async fn read_file(file_path: String) -> Result<String, String> {
-    fs::read_to_string(&file_path)
-        .map_err(|e| e.to_string())
+async fn read_file(file_path: String, scope: State<'_, PathScope>) -> Result<file_meta::FileContent, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let bytes = fs::read(&path)?;
+    let hash = file_meta::content_hash(&bytes);
+    // 自动识别 BOM、UTF-8 和 GBK/GB18030，换行符统一为 \n
+    let decoded = text_encoding::decode(&bytes)?;
+    
+    Ok(file_meta::FileContent {
+        content: decoded.content,
+        hash,
+        modified_time: file_meta::modified_time(&path),
+        encoding: decoded.encoding,
+        line_ending: decoded.line_ending,
+    })
 }
 
 
-// 写入文件
+// 写入文件（原子写入：临时文件 + fsync + 重命名，可选轮换保留 .bak 备份和哈希前置条件）
+// 默认沿用文件原有的编码和换行符，传入 encoding / line_ending 时转换为指定格式
 #[tauri::command]
-async fn write_file(file_path: String, content: String) -> Result<bool, String> {
-    fs::write(&file_path, content)
-        .map(|_| true)
-        .map_err(|e| e.to_string())
+#[allow(clippy::too_many_arguments)]
+async fn write_file(
+    file_path: String,
+    content: String,
+    backup: Option<bool>,
+    expected_hash: Option<String>,
+    encoding: Option<text_encoding::TextEncoding>,
+    line_ending: Option<text_encoding::LineEnding>,
+    scope: State<'_, PathScope>,
+    history: State<'_, history::VersionHistory>,
+    store: State<'_, content_store::ContentStore>,
+) -> Result<bool, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let _guard = file_meta::write_lock();
+    
+    // 乐观并发控制：文件在读取后被外部修改过则拒绝覆盖
+    // 只读取一次磁盘内容，哈希检查、编码识别和版本快照都基于这份内容
+    let existing_bytes = file_meta::read_if_exists(&path)?;
+    if let Some(expected_hash) = &expected_hash {
+        file_meta::check_expected_hash(&path, existing_bytes.as_deref(), expected_hash)?;
+    }
+    
+    // 新文件使用 UTF-8 + LF；已有文件沿用原格式。原文件编码无法识别时，
+    // 除非调用方明确指定了 encoding，否则拒绝写入，避免悄悄把文件转成 UTF-8
+    let existing = match (existing_bytes.as_deref(), encoding) {
+        (Some(bytes), None) => Some(text_encoding::decode(bytes).map_err(|e| {
+            CommandError::new(&e.code, format!("{}，请指定 encoding 后再保存: {}", e.message, file_path))
+        })?),
+        (Some(bytes), Some(_)) => text_encoding::decode(bytes).ok(),
+        (None, _) => None,
+    };
+    let encoding = encoding
+        .or(existing.as_ref().map(|e| e.encoding))
+        .unwrap_or(text_encoding::TextEncoding::Utf8);
+    let line_ending = line_ending
+        .or(existing.as_ref().map(|e| e.line_ending))
+        .unwrap_or(text_encoding::LineEnding::Lf);
+    let bytes = text_encoding::encode(&content, encoding, line_ending);
+    
+    let options = atomic_write::WriteOptions {
+        backup: backup.unwrap_or(false),
+    };
+    save_file(&history, &scope, &store, &path, existing_bytes.as_deref(), &bytes, options)?;
+    Ok(true)
+}
+
+// 覆盖文本文件的公共流程（write_file、移动文章时的链接改写等共用），调用方需持有 file_meta::write_lock：
+// source/ 下的 markdown 覆盖前保存旧版本，重命名前再读取一次确认磁盘内容仍是 previous（None 表示文件原本不存在），
+// 写入后更新文章索引和全文搜索索引；历史记录和索引失败只记录日志，不影响保存本身
+pub(crate) fn save_file(
+    history: &history::VersionHistory,
+    scope: &PathScope,
+    store: &content_store::ContentStore,
+    path: &std::path::Path,
+    previous: Option<&[u8]>,
+    bytes: &[u8],
+    options: atomic_write::WriteOptions,
+) -> Result<(), CommandError> {
+    if let Some(previous) = previous.filter(|previous| *previous != bytes) {
+        if let Err(e) = history::snapshot(history, scope, path, previous) {
+            log::warn!("保存版本历史失败 {}: {}", path.display(), e);
+        }
+    }
+
+    atomic_write::write_atomic_checked(path, bytes, options, || {
+        let current = file_meta::read_if_exists(path)?;
+        match previous {
+            Some(previous) => file_meta::check_expected_hash(path, current.as_deref(), &file_meta::content_hash(previous)),
+            None if current.is_some() => Err(CommandError::new(
+                error::CONFLICT,
+                format!("文件已被其他程序创建: {}", path.display()),
+            )),
+            None => Ok(()),
+        }
+    })?;
+
+    // 失败时下次刷新会重新解析
+    if let Err(e) = content_store::reindex(store, scope, path) {
+        log::warn!("更新文章索引失败 {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+// 读取二进制文件（图片、附件），以原始字节返回，避免 JSON 序列化数组的开销
+#[tauri::command]
+async fn read_file_bytes(file_path: String, scope: State<'_, PathScope>) -> Result<tauri::ipc::Response, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let bytes = fs::read(&path)?;
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+// 写入二进制文件（粘贴的截图、拖入的 PDF 等）
+// 请求体为原始字节，目标路径通过 x-file-path 请求头传递（需 encodeURIComponent 编码）
+#[tauri::command]
+async fn write_file_bytes(request: tauri::ipc::Request<'_>, scope: State<'_, PathScope>) -> Result<String, CommandError> {
+    let tauri::ipc::InvokeBody::Raw(bytes) = request.body() else {
+        return Err(CommandError::new(error::INVALID_PATH, "请求体必须是原始字节"));
+    };
+    let encoded_path = request
+        .headers()
+        .get("x-file-path")
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| CommandError::new(error::INVALID_PATH, "缺少 x-file-path 请求头"))?;
+    let file_path = percent_encoding::percent_decode_str(encoded_path)
+        .decode_utf8()
+        .map_err(|e| CommandError::new(error::INVALID_PATH, e.to_string()))?
+        .to_string();
+    
+    let path = scope.resolve(&file_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    atomic_write::write_atomic(&path, bytes, atomic_write::WriteOptions::default())?;
+    Ok(file_path)
 }
 
-// 删除文件
+// 确保目录存在（不存在则递归创建）
 #[tauri::command]
-async fn delete_file(file_path: String) -> Result<bool, String> {
-    fs::remove_file(&file_path)
+async fn ensure_dir(directory_path: String, scope: State<'_, PathScope>) -> Result<bool, CommandError> {
+    let path = scope.resolve(&directory_path)?;
+    fs::create_dir_all(&path)?;
+    Ok(true)
+}
+
+// 删除文件（移入回收站，文章的资源文件夹一并移入）
+#[tauri::command]
+async fn delete_file(file_path: String, scope: State<'_, PathScope>, app_handle: tauri::AppHandle) -> Result<bool, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let metadata = fs::metadata(&path)?;
+    if metadata.is_dir() {
+        return Err(CommandError::new(error::INVALID_PATH, format!("不能使用 delete_file 删除目录: {}", file_path)));
+    }
+    
+    let mut paths = vec![path.clone()];
+    if let Some(asset_folder) = recycle_bin::asset_folder_of(&path) {
+        paths.push(asset_folder);
+    }
+    
+    recycle_bin::move_to_trash(&app_handle, &paths)
         .map(|_| true)
-        .map_err(|e| e.to_string())
 }
 
 // 复制文件
 #[tauri::command]
-async fn copy_file(source_path: String, destination_path: String) -> Result<String, String> {
+async fn copy_file(source_path: String, destination_path: String, scope: State<'_, PathScope>) -> Result<String, CommandError> {
+    let source = scope.resolve(&source_path)?;
+    let destination = scope.resolve(&destination_path)?;
+    
     // 确保目标目录存在
-    if let Some(parent) = PathBuf::from(&destination_path).parent() {
-        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
     }
     
     // 复制文件
-    fs::copy(&source_path, &destination_path)
+    fs::copy(&source, &destination)
         .map(|_| destination_path)
-        .map_err(|e| e.to_string())
+        .map_err(CommandError::from)
 }
 
 // 列出目录中的文件
 #[tauri::command]
-async fn list_files(directory_path: String) -> Result<Vec<FileInfo>, String> {
-    let entries = fs::read_dir(&directory_path)
-        .map_err(|e| e.to_string())?;
+async fn list_files(directory_path: String, scope: State<'_, PathScope>) -> Result<Vec<FileInfo>, CommandError> {
+    let directory = scope.resolve(&directory_path)?;
+    let entries = fs::read_dir(&directory)?;
     
     let mut files = Vec::new();
     
     for entry in entries {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        let entry = entry?;
+        let metadata = entry.metadata()?;
         let file_name = entry.file_name().to_string_lossy().to_string();
         
         // 忽略隐藏文件
@@ -124,12 +303,12 @@ async fn list_files(directory_path: String) -> Result<Vec<FileInfo>, String> {
         
         if metadata.is_file() {
             let modified_time = metadata.modified()
-                .map(|t| format_system_time(t))
+                .map(format_system_time)
                 .unwrap_or_else(|_| "0".to_string());
             
             files.push(FileInfo {
                 name: file_name,
-                path: entry.path().to_string_lossy().to_string(),
+                path: PathBuf::from(&directory_path).join(entry.file_name()).to_string_lossy().to_string(),
                 is_directory: false,
                 size: metadata.len(),
                 modified_time,
@@ -220,7 +399,12 @@ async fn execute_command(command: String, app_handle: tauri::AppHandle) -> Comma
 
 // 执行 Hexo 命令
 #[tauri::command]
-async fn execute_hexo_command(command: String, working_dir: String) -> CommandResult {
+async fn execute_hexo_command(command: String, working_dir: String, app_handle: tauri::AppHandle) -> HexoCommandResult {
+    run_hexo_command(&command, &working_dir, &app_handle.state::<PathScope>())
+}
+
+// 同步执行 Hexo 命令（定时发布等后台任务也使用）
+pub(crate) fn run_hexo_command(command: &str, working_dir: &str, scope: &PathScope) -> HexoCommandResult {
     let hexo_cmd = if cfg!(target_os = "windows") {
         "hexo.cmd"
     } else {
@@ -229,15 +413,15 @@ async fn execute_hexo_command(command: String, working_dir: String) -> CommandRe
     
     // 解析命令参数，避免引号嵌套问题
     // 例如: command = "new \"测试\"" -> ["new", "测试"]
-    let args: Vec<String> = shell_words::split(&command)
-        .unwrap_or_else(|_| vec![command.clone()]);
+    let args: Vec<String> = shell_words::split(command)
+        .unwrap_or_else(|_| vec![command.to_string()]);
     
     let output = if cfg!(target_os = "windows") {
         // 直接调用 hexo.cmd，传递解析后的参数
         // 这样可以避免 cmd /C 的引号转义问题
         let mut cmd = Command::new(hexo_cmd);
         cmd.args(&args)
-           .current_dir(&working_dir);
+           .current_dir(working_dir);
         #[cfg(target_os = "windows")]
         cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
         cmd.output()
@@ -247,7 +431,7 @@ async fn execute_hexo_command(command: String, working_dir: String) -> CommandRe
         Command::new("sh")
             .arg("-c")
             .arg(&full_command)
-            .current_dir(&working_dir)
+            .current_dir(working_dir)
             .output()
     };
     
@@ -257,31 +441,74 @@ async fn execute_hexo_command(command: String, working_dir: String) -> CommandRe
             let stdout = smart_decode(&output.stdout);
             let stderr = smart_decode(&output.stderr);
             
-            CommandResult {
-                success: output.status.success(),
-                stdout: Some(stdout.clone()),
-                stderr: Some(stderr.clone()),
-                error: if !output.status.success() && stdout.is_empty() && stderr.is_empty() {
-                    Some("命令执行失败，未返回输出".to_string())
-                } else {
-                    None
+            // 失败时解析 YAML / Nunjucks 错误，定位到具体文件和行号
+            let diagnostics = if output.status.success() {
+                Vec::new()
+            } else {
+                diagnostics::parse_hexo_errors(&format!("{}\n{}", stdout, stderr), std::path::Path::new(working_dir), scope)
+            };
+            
+            HexoCommandResult {
+                result: CommandResult {
+                    success: output.status.success(),
+                    stdout: Some(stdout.clone()),
+                    stderr: Some(stderr.clone()),
+                    error: if !output.status.success() && stdout.is_empty() && stderr.is_empty() {
+                        Some("命令执行失败，未返回输出".to_string())
+                    } else {
+                        None
+                    },
                 },
+                diagnostics,
             }
         },
-        Err(e) => CommandResult {
-            success: false,
-            stdout: None,
-            stderr: None,
-            error: Some(format!("命令执行错误: {}", e)),
+        Err(e) => HexoCommandResult {
+            result: CommandResult {
+                success: false,
+                stdout: None,
+                stderr: None,
+                error: Some(format!("命令执行错误: {}", e)),
+            },
+            diagnostics: Vec::new(),
         },
     }
 }
 
 // 验证 Hexo 项目
 #[tauri::command]
-async fn validate_hexo_project(directory_path: String, language: String) -> ValidationResult {
-    let config_path = PathBuf::from(&directory_path).join("_config.yml");
-    let package_path = PathBuf::from(&directory_path).join("package.json");
+async fn validate_hexo_project(directory_path: String, language: String, app_handle: tauri::AppHandle) -> ValidationResult {
+    let result = check_hexo_project(&directory_path, &language);
+    
+    // 只有 _config.yml 不够，还需要 package.json 中的 hexo 依赖和 source 目录，才会注册为文件访问范围
+    if result.valid && !scope::is_hexo_project(std::path::Path::new(&directory_path)) {
+        let message = if language == "en" {
+            "package.json has no hexo dependency or the source directory is missing".to_string()
+        } else {
+            "package.json 中没有 hexo 依赖或缺少 source 目录".to_string()
+        };
+        return ValidationResult { valid: false, message };
+    }
+    
+    // 验证通过的项目目录注册到文件访问范围中（根目录、用户主目录会被拒绝），并加入定时发布的检查列表
+    if result.valid {
+        let root = match scope::register_root(&app_handle, std::path::Path::new(&directory_path)) {
+            Ok(root) => root,
+            Err(e) => {
+                log::warn!("注册项目目录失败 {}: {}", directory_path, e);
+                return ValidationResult { valid: false, message: e.message };
+            }
+        };
+        if let Err(e) = app_handle.state::<scheduler::Scheduler>().ensure_project(&root) {
+            log::warn!("加入定时发布检查列表失败 {}: {}", root.display(), e);
+        }
+    }
+    
+    result
+}
+
+fn check_hexo_project(directory_path: &str, language: &str) -> ValidationResult {
+    let config_path = PathBuf::from(directory_path).join("_config.yml");
+    let package_path = PathBuf::from(directory_path).join("package.json");
     
     if config_path.exists() {
         if let Ok(content) = fs::read_to_string(&config_path) {
@@ -827,7 +1054,6 @@ async fn show_in_folder(path: String) -> Result<(), String> {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
-    .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_dialog::init())
     .plugin(tauri_plugin_shell::init())
     .plugin(tauri_plugin_http::init())
@@ -835,9 +1061,14 @@ pub fn run() {
     .plugin(tauri_plugin_os::init())
     .plugin(tauri_plugin_window_state::Builder::default().build())
     .manage(HexoServer(Mutex::new(None)))
+    .manage(PathScope::new())
+    .manage(watcher::ProjectWatchers::new())
     .invoke_handler(tauri::generate_handler![
         read_file,
         write_file,
+        read_file_bytes,
+        write_file_bytes,
+        ensure_dir,
         delete_file,
         copy_file,
         list_files,
@@ -851,6 +1082,54 @@ pub fn run() {
         maximize_restore_window,
         close_window,
         show_in_folder,
+        diagnostics::parse_hexo_output,
+        scope::unregister_project_root,
+        scope::import_background_image,
+        recycle_bin::list_trash,
+        recycle_bin::restore_from_trash,
+        recycle_bin::empty_trash,
+        walk::walk_files,
+        post_move::move_post,
+        watcher::watch_project,
+        watcher::unwatch_project,
+        dir_ops::create_dir,
+        dir_ops::copy_dir,
+        dir_ops::remove_dir,
+        dir_ops::dir_size,
+        history::list_versions,
+        history::diff_versions,
+        history::restore_version,
+        history::get_history_retention,
+        history::set_history_retention,
+        front_matter::parse_post,
+        front_matter::update_front_matter,
+        post_index::scan_posts,
+        content_store::query_posts,
+        search::search_posts,
+        taxonomy::rename_taxonomy,
+        taxonomy::merge_taxonomy,
+        taxonomy::delete_taxonomy,
+        taxonomy::category_tree,
+        taxonomy::move_category,
+        drafts::list_drafts,
+        drafts::publish_draft,
+        drafts::unpublish_post,
+        scheduler::get_schedule_settings,
+        scheduler::set_schedule_settings,
+        scheduler::schedule_post,
+        scheduler::list_scheduled_posts,
+        scheduler::get_schedule_log,
+        scaffolds::list_scaffolds,
+        scaffolds::read_scaffold,
+        scaffolds::write_scaffold,
+        scaffolds::create_post,
+        slug::get_slug_settings,
+        slug::set_slug_settings,
+        slug::generate_slug,
+        word_count::post_text_stats,
+        word_count::project_text_stats,
+        publish_stats::publish_stats,
+        link_check::check_links,
     ])
     .setup(|app| {
       #[cfg(debug_assertions)]
@@ -869,6 +1148,16 @@ pub fn run() {
         )?;
       }
 
+      // 应用数据目录始终在文件访问范围内
+      let app_data_dir = app.path().app_data_dir()?;
+      fs::create_dir_all(&app_data_dir)?;
+      scope::register_root(app.handle(), &app_data_dir)?;
+      app.manage(history::VersionHistory::new(app_data_dir.join("history")));
+      app.manage(content_store::ContentStore::open_or_recreate(&app_data_dir.join("content.db"))?);
+      app.manage(scheduler::Scheduler::open(app_data_dir.join("scheduler.json")));
+      app.manage(slug::SlugService::open(app_data_dir.join("slug.json")));
+      scheduler::start(app.handle().clone());
+
       // 获取主窗口并监听关闭事件，确保清理 Hexo 服务器
       if let Some(window) = app.get_webview_window("main") {
         let app_handle = app.handle().clone();
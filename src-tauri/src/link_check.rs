@@ -0,0 +1,391 @@
+// 链接检查
+// 解析 source 下的所有 markdown，检查以下引用是否指向存在的文件：
+//   相对路径的链接和图片（[text](../a.md)、![](img.png)、<img src>、引用式链接定义 [id]: a.md），
+//   也会在文章的资源文件夹中查找
+//   以 / 开头的站点路径（/images/a.png），在 source 和当前主题的 source 中查找
+//   {% post_link slug %} / {% post_path slug %}
+//   {% asset_img name %} / {% asset_link name %} / {% asset_path name %}，在文章的资源文件夹中查找
+// 失效的引用附带文件、行号和修复建议（同名文件的新位置，或名称最接近的文章 / 资源）
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use tauri::State;
+use walkdir::WalkDir;
+
+use crate::drafts::site_config;
+use crate::error::{self, CommandError};
+use crate::post_move::{
+    decode_link, is_markdown, is_relative_link, markdown_files, normalize_lexically, post_slug, relative_path, LinkPatterns,
+};
+use crate::scope::PathScope;
+use crate::text_encoding;
+use crate::word_count::CodeBlocks;
+
+// 每个失效引用最多给出的建议数
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenLink {
+    pub file: String,
+    // 从 1 开始
+    pub line: usize,
+    pub column: usize,
+    // "link" | "image" | "sitePath" | "postLink" | "asset"
+    pub kind: &'static str,
+    pub target: String,
+    pub message: String,
+    // 可以替换 target 的写法
+    pub suggestions: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkReport {
+    pub checked_files: usize,
+    pub checked_links: usize,
+    pub broken: Vec<BrokenLink>,
+}
+
+// 检查时共用的项目信息
+struct Site {
+    source: PathBuf,
+    theme_source: Option<PathBuf>,
+    // 站点的 root（例如 /blog/），以 / 开头和结尾
+    root: String,
+    // post_link 可以使用的 slug（相对 _posts 的路径和文件名）
+    slugs: HashSet<String>,
+    // 文件名（小写）到 source 中所有同名文件的映射，用于建议新位置
+    files_by_name: HashMap<String, Vec<PathBuf>>,
+}
+
+fn asset_tag() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"\{%\s*asset_(?:img|link|path)\s+(["']?)([^"'\s%]+)"#).unwrap())
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+// 名称最接近的候选项（差异不超过名称长度的三分之一）
+fn closest<'a>(target: &str, candidates: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let target = target.to_lowercase();
+    let limit = (target.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, &String)> = candidates
+        .map(|candidate| (levenshtein(&target, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    scored.sort();
+    scored.into_iter().take(MAX_SUGGESTIONS).map(|(_, candidate)| candidate.clone()).collect()
+}
+
+impl Site {
+    fn load(project_root: &Path) -> Site {
+        let source = project_root.join("source");
+        let config = site_config(project_root);
+        let theme_source = config
+            .get("theme")
+            .and_then(|v| v.as_str())
+            .map(|theme| project_root.join("themes").join(theme).join("source"))
+            .filter(|path| path.is_dir());
+        let root = config.get("root").and_then(|v| v.as_str()).unwrap_or("/");
+        let root = format!("/{}/", root.trim_matches('/')).replace("//", "/");
+
+        let mut slugs = HashSet::new();
+        let mut files_by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        let entries = WalkDir::new(&source)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !(entry.file_name().to_string_lossy().starts_with('.') || entry.file_name() == "node_modules"))
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file());
+        for entry in entries {
+            let path = entry.into_path();
+            // Hexo 也接受只写文件名的 post_link
+            if let Some(slug) = post_slug(project_root, &path).filter(|_| is_markdown(&path)) {
+                if let Some(stem) = path.file_stem() {
+                    slugs.insert(stem.to_string_lossy().to_string());
+                }
+                slugs.insert(slug);
+            }
+            if let Some(name) = path.file_name() {
+                files_by_name.entry(name.to_string_lossy().to_lowercase()).or_default().push(path);
+            }
+        }
+
+        Site {
+            source,
+            theme_source,
+            root,
+            slugs,
+            files_by_name,
+        }
+    }
+
+    // 同名文件的其他位置，按 format 转换为链接写法
+    fn relocated(&self, target: &Path, format: impl Fn(&Path) -> Option<String>) -> Vec<String> {
+        let Some(name) = target.file_name() else {
+            return Vec::new();
+        };
+        self.files_by_name
+            .get(&name.to_string_lossy().to_lowercase())
+            .map(|paths| paths.iter().filter_map(|path| format(path)).take(MAX_SUGGESTIONS).collect())
+            .unwrap_or_default()
+    }
+
+    // 文件在站点中的路径（/images/a.png）
+    fn site_path(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.source).ok()?;
+        Some(format!("{}{}", self.root, relative.to_string_lossy().replace('\\', "/")))
+    }
+}
+
+// 没有扩展名或 .html 的路径多半是生成的页面（文章、标签页等），无法对照源文件检查
+fn is_generated(path: &str) -> bool {
+    let extension = Path::new(path).extension().map(|ext| ext.to_string_lossy().to_lowercase());
+    path.is_empty() || path.ends_with('/') || matches!(extension.as_deref(), None | Some("html" | "htm"))
+}
+
+struct Checker<'a> {
+    site: &'a Site,
+    file: &'a Path,
+    dir: &'a Path,
+    // 文章的资源文件夹（post_asset_folder），文件不一定存在
+    asset_dir: PathBuf,
+    // 资源文件夹中的文件（相对路径），第一次需要给出建议时再列出
+    asset_files: Option<Vec<String>>,
+    checked: usize,
+    broken: Vec<BrokenLink>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, line: usize, column: usize, kind: &'static str, target: &str, message: String, suggestions: Vec<String>) {
+        self.broken.push(BrokenLink {
+            file: self.file.to_string_lossy().to_string(),
+            line,
+            column,
+            kind,
+            target: target.to_string(),
+            message,
+            suggestions,
+        });
+    }
+
+    fn check_url(&mut self, line: usize, column: usize, kind: &'static str, link: &str) {
+        let path_part = link.split(['#', '?']).next().unwrap_or("");
+        if path_part.is_empty() {
+            return;
+        }
+        let decoded = decode_link(path_part);
+
+        if link.starts_with('/') && !link.starts_with("//") {
+            self.checked += 1;
+            let relative = decoded.strip_prefix(self.site.root.as_str()).unwrap_or(decoded.trim_start_matches('/'));
+            let relative = relative.trim_start_matches('/');
+            let found = [Some(&self.site.source), self.site.theme_source.as_ref()]
+                .into_iter()
+                .flatten()
+                .any(|base| base.join(relative).exists());
+            if !found && !is_generated(relative) {
+                let target = self.site.source.join(relative);
+                let suggestions = self.site.relocated(&target, |path| self.site.site_path(path));
+                self.report(line, column, "sitePath", link, format!("站点中不存在: {}", decoded), suggestions);
+            }
+            return;
+        }
+        if !is_relative_link(link) {
+            return;
+        }
+
+        self.checked += 1;
+        let target = normalize_lexically(&self.dir.join(&decoded));
+        let in_assets = normalize_lexically(&self.asset_dir.join(&decoded));
+        if target.exists() || in_assets.exists() || is_generated(&decoded) {
+            return;
+        }
+        let dir = self.dir;
+        let suggestions = self
+            .site
+            .relocated(&target, |path| Some(relative_path(dir, path).replace(' ', "%20")));
+        self.report(line, column, kind, link, format!("文件不存在: {}", target.display()), suggestions);
+    }
+
+    fn check_post_link(&mut self, line: usize, column: usize, slug: &str) {
+        self.checked += 1;
+        if self.site.slugs.contains(slug) {
+            return;
+        }
+        let suggestions = closest(slug, self.site.slugs.iter());
+        self.report(line, column, "postLink", slug, format!("找不到文章: {}", slug), suggestions);
+    }
+
+    fn check_asset(&mut self, line: usize, column: usize, name: &str) {
+        self.checked += 1;
+        let decoded = decode_link(name);
+        if self.asset_dir.join(&decoded).exists() {
+            return;
+        }
+        let asset_dir = &self.asset_dir;
+        let available = self.asset_files.get_or_insert_with(|| {
+            WalkDir::new(asset_dir)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file())
+                .filter_map(|entry| {
+                    entry
+                        .path()
+                        .strip_prefix(asset_dir)
+                        .ok()
+                        .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                })
+                .collect()
+        });
+        let suggestions = closest(&decoded, available.iter());
+        self.report(
+            line,
+            column,
+            "asset",
+            name,
+            format!("资源文件夹中不存在: {}", self.asset_dir.join(&decoded).display()),
+            suggestions,
+        );
+    }
+}
+
+fn column_of(line: &str, byte_index: usize) -> usize {
+    line[..byte_index].chars().count() + 1
+}
+
+fn check_file(site: &Site, patterns: &LinkPatterns, file: &Path) -> (usize, Vec<BrokenLink>) {
+    let Some(content) = fs::read(file).ok().and_then(|bytes| text_encoding::decode(&bytes).ok()) else {
+        return (0, Vec::new());
+    };
+    let dir = file.parent().unwrap_or(file);
+    let mut checker = Checker {
+        site,
+        file,
+        dir,
+        asset_dir: file.with_extension(""),
+        asset_files: None,
+        checked: 0,
+        broken: Vec::new(),
+    };
+
+    let mut code_blocks = CodeBlocks::default();
+    for (index, line) in content.content.lines().enumerate() {
+        let line_number = index + 1;
+        // 跳过代码块中的示例链接
+        if code_blocks.is_code(line) {
+            continue;
+        }
+
+        for caps in patterns.markdown.captures_iter(line) {
+            let kind = if caps[1].starts_with('!') { "image" } else { "link" };
+            let link = caps.get(3).unwrap();
+            checker.check_url(line_number, column_of(line, link.start()), kind, link.as_str());
+        }
+        for caps in patterns.img_tag.captures_iter(line) {
+            let link = caps.get(2).unwrap();
+            checker.check_url(line_number, column_of(line, link.start()), "image", link.as_str());
+        }
+        for caps in patterns.reference.captures_iter(line) {
+            let link = caps.get(3).unwrap();
+            checker.check_url(line_number, column_of(line, link.start()), "link", link.as_str());
+        }
+        for caps in patterns.post_tag.captures_iter(line) {
+            let slug = caps.get(3).unwrap();
+            checker.check_post_link(line_number, column_of(line, slug.start()), slug.as_str());
+        }
+        for caps in asset_tag().captures_iter(line) {
+            let name = caps.get(2).unwrap();
+            checker.check_asset(line_number, column_of(line, name.start()), name.as_str());
+        }
+    }
+    (checker.checked, checker.broken)
+}
+
+// 检查项目中所有文章的内部链接和资源引用
+#[tauri::command]
+pub async fn check_links(project_path: String, scope: State<'_, PathScope>) -> Result<LinkReport, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    if !project_root.join("source").is_dir() {
+        return Err(CommandError::new(
+            error::NOT_FOUND,
+            format!("不是 Hexo 项目（缺少 source 目录）: {}", project_path),
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let site = Site::load(&project_root);
+        let patterns = LinkPatterns::new();
+        let files = markdown_files(&project_root);
+        let results: Vec<(usize, Vec<BrokenLink>)> = files.par_iter().map(|file| check_file(&site, &patterns, file)).collect();
+
+        let mut report = LinkReport {
+            checked_files: files.len(),
+            checked_links: 0,
+            broken: Vec::new(),
+        };
+        for (checked, broken) in results {
+            report.checked_links += checked;
+            report.broken.extend(broken);
+        }
+        report.broken.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)).then(a.column.cmp(&b.column)));
+        Ok(report)
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_reference_definitions_and_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let posts = dir.path().join("source").join("_posts");
+        fs::create_dir_all(posts.join("a")).unwrap();
+        fs::write(posts.join("a").join("diagram.png"), "").unwrap();
+        fs::write(posts.join("b.md"), "# B\n").unwrap();
+        let post = posts.join("a.md");
+        fs::write(
+            &post,
+            "[ok]: b.md\n[gone]: missing.md \"t\"\n[^1]: not a link\n\
+             {% asset_img diagram.png %}\n{% asset_img diagam.png %}\n{% asset_img diagrm.png %}\n\
+             ```\n[code]: missing.md\n```\n\
+             ````md\n```\n[nested]: missing.md\n````\n\
+             {% codeblock lang:md %}\n[tag]: missing.md\n{% endcodeblock %}\n",
+        )
+        .unwrap();
+
+        let site = Site::load(dir.path());
+        let (checked, broken) = check_file(&site, &LinkPatterns::new(), &post);
+        assert_eq!(checked, 5);
+        let found: Vec<(usize, &str, &str)> = broken.iter().map(|b| (b.line, b.kind, b.target.as_str())).collect();
+        assert_eq!(
+            found,
+            vec![(2, "link", "missing.md"), (5, "asset", "diagam.png"), (6, "asset", "diagrm.png")]
+        );
+        assert_eq!(broken[1].suggestions, vec!["diagram.png"]);
+        assert_eq!(broken[2].suggestions, vec!["diagram.png"]);
+    }
+}
@@ -0,0 +1,272 @@
+// 文章索引
+// 一次调用返回项目中所有文章（source/_posts 和 source/_drafts）的元数据，
+// 解析结果由 content_store 按修改时间和大小持久化，重复扫描时只重新解析变化过的文章
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+use walkdir::WalkDir;
+
+use crate::content_store::{ContentStore, PostQuery};
+use crate::error::{self, CommandError};
+use crate::format_system_time;
+use crate::front_matter::parse_front_matter;
+use crate::post_move::{is_markdown, post_slug};
+use crate::scope::PathScope;
+use crate::search::SearchDocument;
+use crate::text_encoding;
+use crate::word_count::text_stats;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostSummary {
+    pub path: String,
+    // 相对于项目根目录，使用 / 分隔
+    pub relative_path: String,
+    // post_link 使用的 slug（草稿为 None）
+    pub slug: Option<String>,
+    pub title: Option<String>,
+    pub date: Option<String>,
+    pub updated: Option<String>,
+    pub tags: Vec<String>,
+    // 每一项是一条分类路径，例如 categories: [技术, Rust] 为 [["技术", "Rust"]]
+    pub categories: Vec<Vec<String>>,
+    // 位于 source/_drafts
+    pub draft: bool,
+    // 不是草稿且没有设置 published: false
+    pub published: bool,
+    pub word_count: usize,
+    // 正文中有 <!-- more --> 或 front-matter 中设置了 excerpt
+    pub has_excerpt: bool,
+    pub size: u64,
+    pub modified_time: String,
+    // front-matter 解析失败时的错误信息，此时其他元数据为空
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanResult {
+    pub posts: Vec<PostSummary>,
+    // 本次重新解析的文章数
+    pub parsed: usize,
+    // 未变化、直接使用已存数据的文章数
+    pub cached: usize,
+}
+
+pub(crate) fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// tags 可以是单个字符串或列表
+pub(crate) fn tag_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(scalar_to_string).collect(),
+        Some(value) => scalar_to_string(value).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+// 与 Hexo 的规则一致：平铺的列表表示一条层级路径；
+// 列表中包含子列表时，每个子列表是一条路径，单个字符串是一个顶级分类
+pub(crate) fn category_paths(value: Option<&Value>) -> Vec<Vec<String>> {
+    match value {
+        Some(Value::Array(items)) if items.iter().any(Value::is_array) => items
+            .iter()
+            .map(|item| match item {
+                Value::Array(path) => path.iter().filter_map(scalar_to_string).collect(),
+                other => scalar_to_string(other).into_iter().collect(),
+            })
+            .filter(|path: &Vec<String>| !path.is_empty())
+            .collect(),
+        Some(Value::Array(items)) => {
+            let path: Vec<String> = items.iter().filter_map(scalar_to_string).collect();
+            if path.is_empty() {
+                Vec::new()
+            } else {
+                vec![path]
+            }
+        }
+        Some(value) => scalar_to_string(value).map(|name| vec![vec![name]]).unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+// 文章所在的目录：source/_posts 和 source/_drafts
+pub(crate) fn post_files(project_root: &Path) -> Vec<PathBuf> {
+    ["_posts", "_drafts"]
+        .iter()
+        .flat_map(|dir| {
+            WalkDir::new(project_root.join("source").join(dir))
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file() && is_markdown(entry.path()))
+                .map(|entry| entry.into_path())
+        })
+        .collect()
+}
+
+// Hexo 的摘要分隔标记
+fn more_tag() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"(?i)<!--\s*more\s*-->").unwrap())
+}
+
+// 解析单篇文章的元数据，同时返回建立搜索索引所需的原文
+pub(crate) fn summarize(project_root: &Path, path: &Path, size: u64, modified: SystemTime) -> (PostSummary, SearchDocument) {
+    let relative = path.strip_prefix(project_root).unwrap_or(path);
+    let draft = relative.starts_with(Path::new("source").join("_drafts"));
+    let mut summary = PostSummary {
+        path: path.to_string_lossy().to_string(),
+        relative_path: relative.to_string_lossy().replace('\\', "/"),
+        slug: if draft { None } else { post_slug(project_root, path) },
+        title: None,
+        date: None,
+        updated: None,
+        tags: Vec::new(),
+        categories: Vec::new(),
+        draft,
+        published: !draft,
+        word_count: 0,
+        has_excerpt: false,
+        size,
+        modified_time: format_system_time(modified),
+        error: None,
+    };
+
+    let parsed = fs::read(path)
+        .map_err(CommandError::from)
+        .and_then(|bytes| text_encoding::decode(&bytes))
+        .and_then(|decoded| parse_front_matter(&decoded.content));
+    let front_matter = match parsed {
+        Ok(front_matter) => front_matter,
+        Err(e) => {
+            summary.error = Some(e.message);
+            return (summary, SearchDocument::default());
+        }
+    };
+
+    let data = &front_matter.data;
+    summary.title = data.get("title").and_then(scalar_to_string);
+    summary.date = data.get("date").and_then(scalar_to_string);
+    summary.updated = data.get("updated").and_then(scalar_to_string);
+    summary.tags = tag_list(data.get("tags"));
+    summary.categories = category_paths(data.get("categories"));
+    summary.published = !draft && data.get("published") != Some(&Value::Bool(false));
+    summary.word_count = text_stats(&front_matter.body).words;
+    summary.has_excerpt = more_tag().is_match(&front_matter.body) || data.get("excerpt").is_some_and(|v| !v.is_null());
+
+    let document = SearchDocument {
+        title: summary.title.clone().unwrap_or_default(),
+        front_matter: front_matter.raw,
+        body: front_matter.body,
+    };
+    (summary, document)
+}
+
+// 将 front-matter 中的日期规范化为 "YYYY-MM-DD HH:MM:SS"，便于排序和比较；无法识别时返回 None
+pub(crate) fn normalize_date(date: &str) -> Option<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"^\s*(\d{4})[-/](\d{1,2})[-/](\d{1,2})(?:[ T](\d{1,2}):(\d{1,2})(?::(\d{1,2}))?)?").unwrap()
+    });
+    let caps = pattern.captures(date)?;
+    let part = |index: usize| caps.get(index).map(|m| m.as_str().parse::<u32>().unwrap_or(0)).unwrap_or(0);
+    Some(format!(
+        "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+        &caps[1],
+        part(2),
+        part(3),
+        part(4),
+        part(5),
+        part(6)
+    ))
+}
+
+// 扫描项目中的所有文章并返回元数据；只重新解析修改过的文章，结果保存在内容数据库中
+#[tauri::command]
+pub async fn scan_posts(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<ScanResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    if !project_root.join("source").is_dir() {
+        return Err(CommandError::new(
+            error::NOT_FOUND,
+            format!("不是 Hexo 项目（缺少 source 目录）: {}", project_path),
+        ));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        let stats = store.refresh(&project_root)?;
+        let query = PostQuery {
+            sort_by: Some("path".to_string()),
+            sort_order: Some("asc".to_string()),
+            page_size: Some(usize::MAX),
+            ..PostQuery::default()
+        };
+        let page = store.query(&project_root, Path::new(&project_path), &query)?;
+        Ok(ScanResult {
+            posts: page.posts,
+            parsed: stats.parsed,
+            cached: stats.cached,
+        })
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(value: Value) -> Vec<Vec<String>> {
+        category_paths(Some(&value))
+    }
+
+    #[test]
+    fn flat_list_is_one_hierarchical_path() {
+        assert_eq!(paths(json!(["技术", "Rust"])), vec![vec!["技术", "Rust"]]);
+        assert_eq!(paths(json!("随笔")), vec![vec!["随笔"]]);
+        assert_eq!(paths(json!([])), Vec::<Vec<String>>::new());
+        assert_eq!(category_paths(None), Vec::<Vec<String>>::new());
+    }
+
+    #[test]
+    fn nested_lists_are_parallel_paths() {
+        assert_eq!(
+            paths(json!([["技术", "Rust"], "随笔", [], [2024]])),
+            vec![vec!["技术", "Rust"], vec!["随笔"], vec!["2024"]]
+        );
+    }
+
+    #[test]
+    fn normalizes_dates() {
+        assert_eq!(normalize_date("2024-1-5").as_deref(), Some("2024-01-05 00:00:00"));
+        assert_eq!(normalize_date("2024/01/05 9:30").as_deref(), Some("2024-01-05 09:30:00"));
+        assert_eq!(normalize_date(" 2024-01-05T09:30:15+08:00").as_deref(), Some("2024-01-05 09:30:15"));
+        assert_eq!(normalize_date("Jan 5, 2024"), None);
+        assert_eq!(normalize_date("2024-01"), None);
+    }
+
+    #[test]
+    fn keeps_slashes_in_names() {
+        assert_eq!(paths(json!(["A/B", "C"])), vec![vec!["A/B", "C"]]);
+    }
+}
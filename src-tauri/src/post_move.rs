@@ -0,0 +1,499 @@
+// 移动 / 重命名文章
+// 同时移动 post_asset_folder 资源文件夹，并更新其他文章中指向该文章或其资源的
+// 相对链接和 {% post_link %} / {% post_path %} 引用。
+// 先在内存中计算所有改写，再移动文件，最后写入改写；写入失败时撤销已写入的改写和移动
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use regex::{Captures, Regex};
+use serde::Serialize;
+use tauri::State;
+use walkdir::WalkDir;
+
+use crate::atomic_write::{self, WriteOptions};
+use crate::content_store::{self, ContentStore};
+use crate::error::{self, CommandError};
+use crate::dir_ops::move_path;
+use crate::file_meta;
+use crate::history::VersionHistory;
+use crate::recycle_bin::asset_folder_of;
+use crate::save_file;
+use crate::scope::PathScope;
+use crate::taxonomy::SkippedPost;
+use crate::text_encoding;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MovePostResult {
+    pub new_path: String,
+    pub new_asset_folder: Option<String>,
+    // 内容被改写的文件（不含移动本身）
+    pub changed_files: Vec<String>,
+    // 无法读取或解码、链接未能更新的文件
+    pub skipped_files: Vec<SkippedPost>,
+}
+
+// relocate_post 的结果
+pub(crate) struct Relocated {
+    pub(crate) new_assets: Option<PathBuf>,
+    pub(crate) changed_files: Vec<String>,
+    pub(crate) skipped_files: Vec<SkippedPost>,
+}
+
+// 一个待写入的链接改写：original 为计算改写时读取的原始字节，写入前会确认文件未被修改
+struct PlannedRewrite {
+    // 写入位置（被移动的文章为移动后的路径）
+    path: PathBuf,
+    original: Vec<u8>,
+    updated: Vec<u8>,
+}
+
+// 去掉 "." 和 ".." 的纯字面路径规范化（不访问文件系统）
+pub(crate) fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() {
+                    result.push("..");
+                }
+            }
+            other => result.push(other.as_os_str()),
+        }
+    }
+    result
+}
+
+// 计算从 from_dir 到 to 的相对路径（统一使用 / 分隔）
+pub(crate) fn relative_path(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to_components: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(to_components.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = Vec::new();
+    for _ in common..from.len() {
+        parts.push("..".to_string());
+    }
+    for component in &to_components[common..] {
+        parts.push(component.as_os_str().to_string_lossy().to_string());
+    }
+    parts.join("/")
+}
+
+// 判断链接是否为可解析的本地相对路径
+pub(crate) fn is_relative_link(link: &str) -> bool {
+    !(link.is_empty()
+        || link.starts_with('/')
+        || link.starts_with('#')
+        || link.starts_with("data:")
+        || link.starts_with("mailto:")
+        || link.contains("://"))
+}
+
+pub(crate) fn decode_link(link: &str) -> String {
+    percent_encoding::percent_decode_str(link)
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+// 文章在 post_link 中使用的 slug：相对于 source/_posts 的路径，去掉扩展名
+pub(crate) fn post_slug(project_root: &Path, post_path: &Path) -> Option<String> {
+    let relative = post_path.strip_prefix(project_root.join("source").join("_posts")).ok()?;
+    Some(relative.with_extension("").to_string_lossy().replace('\\', "/"))
+}
+
+pub(crate) fn is_markdown(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+        .unwrap_or(false)
+}
+
+// source 目录下的所有 markdown 文件
+pub(crate) fn markdown_files(project_root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(project_root.join("source"))
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| entry.file_name() != "node_modules")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && is_markdown(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+struct Relocation {
+    old_post: PathBuf,
+    new_post: PathBuf,
+    old_assets: Option<PathBuf>,
+    new_assets: Option<PathBuf>,
+}
+
+impl Relocation {
+    // 将旧位置下的目标映射到新位置
+    fn map(&self, target: &Path) -> Option<PathBuf> {
+        if target == self.old_post {
+            return Some(self.new_post.clone());
+        }
+        let (old_assets, new_assets) = (self.old_assets.as_ref()?, self.new_assets.as_ref()?);
+        let rest = target.strip_prefix(old_assets).ok()?;
+        Some(new_assets.join(rest))
+    }
+}
+
+pub(crate) struct LinkPatterns {
+    // 匹配 [text](url) 和 ![alt](url "title")
+    pub(crate) markdown: Regex,
+    pub(crate) img_tag: Regex,
+    // 引用式链接的定义 [id]: url "title"（不含脚注 [^1]: ...）
+    pub(crate) reference: Regex,
+    // {% post_link slug %} / {% post_path slug %}
+    pub(crate) post_tag: Regex,
+}
+
+impl LinkPatterns {
+    pub(crate) fn new() -> Self {
+        LinkPatterns {
+            markdown: Regex::new(r#"(!?\[[^\]]*\]\()(<?)([^)\s>]+)(>?)((?:\s+"[^"]*")?\))"#).unwrap(),
+            img_tag: Regex::new(r#"(<img\s[^>]*?src=["'])([^"']+)(["'])"#).unwrap(),
+            reference: Regex::new(r#"(?m)^( {0,3}\[[^\]^][^\]]*\]:[ \t]*)(<?)([^\s>]+)(>?)"#).unwrap(),
+            post_tag: Regex::new(r#"(\{%\s*post_(?:link|path)\s+)(["']?)([^\s"'%]+)(["']?)"#).unwrap(),
+        }
+    }
+}
+
+// 改写一篇文章中的链接；file_dir_before / file_dir_after 为文章移动前后所在目录
+fn rewrite_content(
+    patterns: &LinkPatterns,
+    content: &str,
+    file_dir_before: &Path,
+    file_dir_after: &Path,
+    relocation: &Relocation,
+    slugs: Option<(&str, &str)>,
+) -> Option<String> {
+    let rewrite_link = |link: &str| -> Option<String> {
+        if !is_relative_link(link) {
+            return None;
+        }
+        let (path_part, suffix) = match link.find(['#', '?']) {
+            Some(index) => (&link[..index], &link[index..]),
+            None => (link, ""),
+        };
+        let target = normalize_lexically(&file_dir_before.join(decode_link(path_part)));
+        // 目标本身被移动时映射到新位置；否则仅在文章自身换了目录时重新计算相对路径
+        let new_target = match relocation.map(&target) {
+            Some(mapped) => mapped,
+            None if file_dir_before == file_dir_after => return None,
+            None => target,
+        };
+        let new_link = relative_path(file_dir_after, &new_target).replace(' ', "%20");
+        let new_link = format!("{}{}", new_link, suffix);
+        if new_link == link {
+            None
+        } else {
+            Some(new_link)
+        }
+    };
+
+    let mut changed = false;
+    let result = patterns.markdown.replace_all(content, |caps: &Captures| match rewrite_link(&caps[3]) {
+        Some(new_link) => {
+            changed = true;
+            format!("{}{}{}{}{}", &caps[1], &caps[2], new_link, &caps[4], &caps[5])
+        }
+        None => caps[0].to_string(),
+    });
+    let result = patterns.img_tag.replace_all(&result, |caps: &Captures| match rewrite_link(&caps[2]) {
+        Some(new_link) => {
+            changed = true;
+            format!("{}{}{}", &caps[1], new_link, &caps[3])
+        }
+        None => caps[0].to_string(),
+    });
+    let result = patterns.reference.replace_all(&result, |caps: &Captures| match rewrite_link(&caps[3]) {
+        Some(new_link) => {
+            changed = true;
+            format!("{}{}{}{}", &caps[1], &caps[2], new_link, &caps[4])
+        }
+        None => caps[0].to_string(),
+    });
+    let result = match slugs {
+        Some((old_slug, new_slug)) => patterns
+            .post_tag
+            .replace_all(&result, |caps: &Captures| {
+                if &caps[3] == old_slug {
+                    changed = true;
+                    format!("{}{}{}{}", &caps[1], &caps[2], new_slug, &caps[4])
+                } else {
+                    caps[0].to_string()
+                }
+            })
+            .to_string(),
+        None => result.to_string(),
+    };
+
+    if changed {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+// 移动文章及其资源文件夹，并更新引用
+#[tauri::command]
+pub async fn move_post(
+    project_path: String,
+    source_path: String,
+    destination_path: String,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<MovePostResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let old_post = scope.resolve(&source_path)?;
+    let new_post = scope.resolve(&destination_path)?;
+
+    if !old_post.starts_with(&project_root) || !new_post.starts_with(&project_root) {
+        return Err(CommandError::new(error::OUT_OF_SCOPE, "文章必须位于项目目录内"));
+    }
+    if !old_post.is_file() {
+        return Err(CommandError::new(error::NOT_FOUND, format!("文章不存在: {}", source_path)));
+    }
+    if !is_markdown(&new_post) {
+        return Err(CommandError::new(error::INVALID_PATH, "目标文件必须是 .md 或 .markdown 文件"));
+    }
+    let relocated = relocate_post(&history, &scope, &store, &project_root, &old_post, &new_post)?;
+    Ok(MovePostResult {
+        new_path: destination_path,
+        new_asset_folder: relocated.new_assets.map(|p| p.to_string_lossy().to_string()),
+        changed_files: relocated.changed_files,
+        skipped_files: relocated.skipped_files,
+    })
+}
+
+// 计算所有需要改写的文件；按原有编码解码，改写后以原有编码和换行符编码。
+// 无法读取或解码的文件放入 skipped，不中断整个操作
+fn plan_rewrites(project_root: &Path, relocation: &Relocation) -> (Vec<PlannedRewrite>, Vec<SkippedPost>) {
+    let old_slug = post_slug(project_root, &relocation.old_post);
+    let new_slug = post_slug(project_root, &relocation.new_post);
+    let slugs = match (&old_slug, &new_slug) {
+        (Some(old_slug), Some(new_slug)) => Some((old_slug.as_str(), new_slug.as_str())),
+        _ => None,
+    };
+
+    let patterns = LinkPatterns::new();
+    let mut rewrites = Vec::new();
+    let mut skipped = Vec::new();
+    for file in markdown_files(project_root) {
+        // 资源文件夹内的 markdown 会随文件夹移动，不单独处理
+        if relocation.old_assets.as_ref().is_some_and(|assets| file.starts_with(assets)) {
+            continue;
+        }
+        let decoded = fs::read(&file)
+            .map_err(CommandError::from)
+            .and_then(|bytes| text_encoding::decode(&bytes).map(|decoded| (bytes, decoded)));
+        let (original, decoded) = match decoded {
+            Ok(decoded) => decoded,
+            Err(e) => {
+                skipped.push(SkippedPost {
+                    path: file.to_string_lossy().to_string(),
+                    error: e.message,
+                });
+                continue;
+            }
+        };
+
+        let is_moved_post = file == relocation.old_post;
+        let dir_before = file.parent().unwrap_or(project_root).to_path_buf();
+        let dir_after = if is_moved_post {
+            relocation.new_post.parent().unwrap_or(project_root).to_path_buf()
+        } else {
+            dir_before.clone()
+        };
+
+        if let Some(content) = rewrite_content(&patterns, &decoded.content, &dir_before, &dir_after, relocation, slugs) {
+            rewrites.push(PlannedRewrite {
+                path: if is_moved_post { relocation.new_post.clone() } else { file },
+                original,
+                updated: text_encoding::encode(&content, decoded.encoding, decoded.line_ending),
+            });
+        }
+    }
+    (rewrites, skipped)
+}
+
+// 撤销移动：把已写入的改写恢复为原内容，再把资源文件夹和文章移回原处；返回未能恢复的路径
+fn rollback(relocation: &Relocation, written: &[&PlannedRewrite], assets_moved: bool) -> Vec<String> {
+    let mut failed = Vec::new();
+    for write in written.iter().rev() {
+        if atomic_write::write_atomic(&write.path, &write.original, WriteOptions::default()).is_err() {
+            failed.push(write.path.to_string_lossy().to_string());
+        }
+    }
+    if let (true, Some(old_assets), Some(new_assets)) = (assets_moved, &relocation.old_assets, &relocation.new_assets) {
+        if move_path(new_assets, old_assets).is_err() {
+            failed.push(new_assets.to_string_lossy().to_string());
+        }
+    }
+    if move_path(&relocation.new_post, &relocation.old_post).is_err() {
+        failed.push(relocation.new_post.to_string_lossy().to_string());
+    }
+    failed
+}
+
+// 移动文章及其资源文件夹并改写引用（经过版本历史和索引更新）；
+// 调用方负责检查路径是否在项目内
+pub(crate) fn relocate_post(
+    history: &VersionHistory,
+    scope: &PathScope,
+    store: &ContentStore,
+    project_root: &Path,
+    old_post: &Path,
+    new_post: &Path,
+) -> Result<Relocated, CommandError> {
+    if new_post.exists() {
+        return Err(CommandError::new(error::ALREADY_EXISTS, format!("目标文件已存在: {}", new_post.display())));
+    }
+
+    let old_assets = asset_folder_of(old_post);
+    let new_assets = old_assets.as_ref().map(|_| new_post.with_extension(""));
+    if let Some(new_assets) = &new_assets {
+        if new_assets.exists() {
+            return Err(CommandError::new(
+                error::ALREADY_EXISTS,
+                format!("目标资源文件夹已存在: {}", new_assets.display()),
+            ));
+        }
+    }
+
+    let relocation = Relocation {
+        old_post: old_post.to_path_buf(),
+        new_post: new_post.to_path_buf(),
+        old_assets,
+        new_assets,
+    };
+    let _guard = file_meta::write_lock();
+
+    // 1. 在内存中计算所有改写
+    let (rewrites, skipped_files) = plan_rewrites(project_root, &relocation);
+
+    // 2. 移动文章和资源文件夹
+    move_path(old_post, new_post)?;
+    if let (Some(old_assets), Some(new_assets)) = (&relocation.old_assets, &relocation.new_assets) {
+        if let Err(e) = move_path(old_assets, new_assets) {
+            // 资源文件夹移动失败时把文章移回原处
+            let _ = move_path(new_post, old_post);
+            return Err(e.into());
+        }
+    }
+
+    // 3. 写入改写；任何一个失败都撤销整个操作
+    let mut written: Vec<&PlannedRewrite> = Vec::new();
+    for rewrite in &rewrites {
+        if let Err(e) = save_file(history, scope, store, &rewrite.path, Some(&rewrite.original), &rewrite.updated, WriteOptions::default()) {
+            let failed = rollback(&relocation, &written, relocation.old_assets.is_some());
+            for path in [old_post, new_post] {
+                let _ = content_store::reindex(store, scope, path);
+            }
+            if failed.is_empty() {
+                return Err(CommandError::new(&e.code, format!("更新 {} 中的链接失败，已撤销移动: {}", rewrite.path.display(), e.message)));
+            }
+            let message = format!("{}；且以下文件未能恢复: {}", e.message, failed.join(", "));
+            return Err(CommandError::new(&e.code, message).with_details(serde_json::json!({ "unrestored": failed })));
+        }
+        written.push(rewrite);
+    }
+
+    // 旧位置移出索引，新位置加入索引（被改写的文件已在 save_file 中更新）
+    for path in [old_post, new_post] {
+        if let Err(e) = content_store::reindex(store, scope, path) {
+            log::warn!("更新文章索引失败 {}: {}", path.display(), e);
+        }
+    }
+
+    Ok(Relocated {
+        new_assets: relocation.new_assets,
+        changed_files: rewrites.iter().map(|rewrite| rewrite.path.to_string_lossy().to_string()).collect(),
+        skipped_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_encoding::{LineEnding, TextEncoding};
+
+    fn relocation(root: &Path, old_post: &str, new_post: &str, assets: bool) -> Relocation {
+        let old_post = root.join(old_post);
+        let new_post = root.join(new_post);
+        Relocation {
+            old_assets: assets.then(|| old_post.with_extension("")),
+            new_assets: assets.then(|| new_post.with_extension("")),
+            old_post,
+            new_post,
+        }
+    }
+
+    #[test]
+    fn rewrites_links_to_moved_post_and_assets() {
+        let root = Path::new("/blog/source");
+        let relocation = relocation(root, "_posts/a.md", "_posts/2024/b.md", true);
+        let patterns = LinkPatterns::new();
+        let content = "[A](a.md#top) ![img](a/pic%201.png \"t\") <img src=\"a/x.png\"> [other](c.md) {% post_link a %}\n\n[a]: a.md \"A\"\n  [pic]: <a/x.png>\n[^a]: a.md";
+        let dir = root.join("_posts");
+        let result = rewrite_content(&patterns, content, &dir, &dir, &relocation, Some(("a", "2024/b"))).unwrap();
+        assert_eq!(
+            result,
+            "[A](2024/b.md#top) ![img](2024/b/pic%201.png \"t\") <img src=\"2024/b/x.png\"> [other](c.md) {% post_link 2024/b %}\n\n[a]: 2024/b.md \"A\"\n  [pic]: <2024/b/x.png>\n[^a]: a.md"
+        );
+    }
+
+    #[test]
+    fn rewrites_relative_links_of_the_moved_post_itself() {
+        let root = Path::new("/blog/source");
+        let relocation = relocation(root, "_posts/a.md", "_posts/2024/a.md", false);
+        let patterns = LinkPatterns::new();
+        let content = "[c](c.md) [web](https://example.com/x.md) [anchor](#top)";
+        let result = rewrite_content(&patterns, content, &root.join("_posts"), &root.join("_posts/2024"), &relocation, None).unwrap();
+        assert_eq!(result, "[c](../c.md) [web](https://example.com/x.md) [anchor](#top)");
+    }
+
+    #[test]
+    fn leaves_unrelated_content_untouched() {
+        let root = Path::new("/blog/source");
+        let relocation = relocation(root, "_posts/a.md", "_posts/b.md", false);
+        let dir = root.join("_posts");
+        let content = "[c](c.md) {% post_link c %}";
+        assert!(rewrite_content(&LinkPatterns::new(), content, &dir, &dir, &relocation, Some(("a", "b"))).is_none());
+    }
+
+    #[test]
+    fn plan_keeps_encoding_and_reports_undecodable_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let posts = dir.path().join("source").join("_posts");
+        fs::create_dir_all(&posts).unwrap();
+        fs::write(posts.join("a.md"), "# A\n").unwrap();
+        let gbk = text_encoding::encode("中文\n[A](a.md)\n", TextEncoding::Gb18030, LineEnding::Crlf);
+        fs::write(posts.join("gbk.md"), &gbk).unwrap();
+        fs::write(posts.join("broken.md"), [0x5b, 0x41, 0x5d, 0x28, 0xff, 0xff, 0x29]).unwrap();
+
+        let relocation = relocation(&posts, "a.md", "b.md", false);
+        let (rewrites, skipped) = plan_rewrites(dir.path(), &relocation);
+
+        assert_eq!(rewrites.len(), 1);
+        assert_eq!(rewrites[0].path, posts.join("gbk.md"));
+        assert_eq!(rewrites[0].original, gbk);
+        let decoded = text_encoding::decode(&rewrites[0].updated).unwrap();
+        assert_eq!(decoded.encoding, TextEncoding::Gb18030);
+        assert_eq!(decoded.line_ending, LineEnding::Crlf);
+        assert_eq!(decoded.content, "中文\n[A](b.md)\n");
+
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].path.ends_with("broken.md"));
+    }
+}
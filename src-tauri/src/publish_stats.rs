@@ -0,0 +1,289 @@
+// 发布统计
+// 按 front-matter 的 date（以及 updated）统计每月、每年的发布数量和字数，以及标签、分类随时间的变化。
+// 不使用文件修改时间：git clone 或批量复制后所有文章的修改时间都会变成当天
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::content_store::{ContentStore, DatedPost};
+use crate::error::{self, CommandError};
+use crate::scope::PathScope;
+
+const DEFAULT_TOP_N: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodStats {
+    // "2024" 或 "2024-03"
+    pub period: String,
+    // 该时间段内发布（date）的文章数和字数
+    pub posts: usize,
+    pub words: usize,
+    // 该时间段内更新（updated）的文章数
+    pub updated: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendSeries {
+    pub name: String,
+    pub total: usize,
+    // 与 periods 一一对应的文章数
+    pub counts: Vec<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishStats {
+    pub total_posts: usize,
+    pub total_words: usize,
+    // 没有 date 或无法识别的文章数（不计入按时间的统计）
+    pub undated: usize,
+    // 从最早到最晚连续排列，没有文章的月份 / 年份为 0
+    pub months: Vec<PeriodStats>,
+    pub years: Vec<PeriodStats>,
+    // 趋势使用的时间段（与 granularity 对应，即 months 或 years 的 period）
+    pub periods: Vec<String>,
+    // 文章数最多的标签 / 顶级分类
+    pub tag_trends: Vec<TrendSeries>,
+    pub category_trends: Vec<TrendSeries>,
+}
+
+// 从 "YYYY-MM-DD HH:MM:SS" 中取出年月
+fn year_month(date: &str) -> Option<(i32, u32)> {
+    let year = date.get(0..4)?.parse().ok()?;
+    let month = date.get(5..7)?.parse().ok().filter(|month| (1..=12).contains(month))?;
+    Some((year, month))
+}
+
+// 从最早到最晚的连续时间段
+fn continuous_periods(first: (i32, u32), last: (i32, u32), by_month: bool) -> Vec<String> {
+    let mut periods = Vec::new();
+    if by_month {
+        let (mut year, mut month) = first;
+        while (year, month) <= last {
+            periods.push(format!("{}-{:02}", year, month));
+            month += 1;
+            if month > 12 {
+                month = 1;
+                year += 1;
+            }
+        }
+    } else {
+        periods.extend((first.0..=last.0).map(|year| year.to_string()));
+    }
+    periods
+}
+
+fn period_of(date: (i32, u32), by_month: bool) -> String {
+    if by_month {
+        format!("{}-{:02}", date.0, date.1)
+    } else {
+        date.0.to_string()
+    }
+}
+
+fn period_stats(posts: &[DatedPost], first: (i32, u32), last: (i32, u32), by_month: bool) -> Vec<PeriodStats> {
+    let mut stats: BTreeMap<String, PeriodStats> = continuous_periods(first, last, by_month)
+        .into_iter()
+        .map(|period| (period.clone(), PeriodStats { period, ..PeriodStats::default() }))
+        .collect();
+    for post in posts {
+        if let Some(date) = post.date.as_deref().and_then(year_month) {
+            if let Some(entry) = stats.get_mut(&period_of(date, by_month)) {
+                entry.posts += 1;
+                entry.words += post.word_count;
+            }
+        }
+        if let Some(updated) = post.updated.as_deref().and_then(year_month) {
+            if let Some(entry) = stats.get_mut(&period_of(updated, by_month)) {
+                entry.updated += 1;
+            }
+        }
+    }
+    stats.into_values().collect()
+}
+
+// 按发布时间统计每个名称的文章数，只保留总数最多的 top_n 个
+fn trends<'a>(
+    posts: &'a [DatedPost],
+    names: impl Fn(&'a DatedPost) -> &'a [String],
+    periods: &[String],
+    by_month: bool,
+    top_n: usize,
+) -> Vec<TrendSeries> {
+    let index: HashMap<&str, usize> = periods.iter().enumerate().map(|(i, period)| (period.as_str(), i)).collect();
+    let mut series: HashMap<&str, Vec<usize>> = HashMap::new();
+    for post in posts {
+        let Some(position) = post
+            .date
+            .as_deref()
+            .and_then(year_month)
+            .and_then(|date| index.get(period_of(date, by_month).as_str()).copied())
+        else {
+            continue;
+        };
+        for name in names(post) {
+            series.entry(name.as_str()).or_insert_with(|| vec![0; periods.len()])[position] += 1;
+        }
+    }
+
+    let mut result: Vec<TrendSeries> = series
+        .into_iter()
+        .map(|(name, counts)| TrendSeries {
+            name: name.to_string(),
+            total: counts.iter().sum(),
+            counts,
+        })
+        .collect();
+    result.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+    result.truncate(top_n);
+    result
+}
+
+fn build_stats(posts: Vec<DatedPost>, by_month: bool, top_n: usize) -> PublishStats {
+    let dates: Vec<(i32, u32)> = posts
+        .iter()
+        .flat_map(|post| [post.date.as_deref(), post.updated.as_deref()])
+        .flatten()
+        .filter_map(year_month)
+        .collect();
+    let undated = posts.iter().filter(|post| post.date.as_deref().and_then(year_month).is_none()).count();
+    let (months, years) = match (dates.iter().min(), dates.iter().max()) {
+        (Some(&first), Some(&last)) => (period_stats(&posts, first, last, true), period_stats(&posts, first, last, false)),
+        _ => (Vec::new(), Vec::new()),
+    };
+    let periods: Vec<String> = if by_month { &months } else { &years }
+        .iter()
+        .map(|stats| stats.period.clone())
+        .collect();
+
+    PublishStats {
+        total_posts: posts.len(),
+        total_words: posts.iter().map(|post| post.word_count).sum(),
+        undated,
+        tag_trends: trends(&posts, |post| &post.tags, &periods, by_month, top_n),
+        category_trends: trends(&posts, |post| &post.categories, &periods, by_month, top_n),
+        months,
+        years,
+        periods,
+    }
+}
+
+// 统计已发布文章（不含草稿和 published: false）；granularity 为 "month"（默认）或 "year"，决定趋势的时间粒度
+#[tauri::command]
+pub async fn publish_stats(
+    project_path: String,
+    granularity: Option<String>,
+    top_n: Option<usize>,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<PublishStats, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let by_month = match granularity.as_deref() {
+        None | Some("month") => true,
+        Some("year") => false,
+        Some(other) => {
+            return Err(CommandError::new(error::INVALID_ARGUMENT, format!("无效的统计粒度: {}", other)));
+        }
+    };
+    let top_n = top_n.unwrap_or(DEFAULT_TOP_N);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        store.refresh(&project_root)?;
+        let posts = store.dated_posts(&project_root)?;
+        Ok(build_stats(posts, by_month, top_n))
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(date: Option<&str>, updated: Option<&str>, words: usize, tags: &[&str]) -> DatedPost {
+        DatedPost {
+            date: date.map(str::to_string),
+            updated: updated.map(str::to_string),
+            word_count: words,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            categories: Vec::new(),
+        }
+    }
+
+    fn periods(stats: &[PeriodStats]) -> Vec<(&str, usize, usize, usize)> {
+        stats
+            .iter()
+            .map(|stats| (stats.period.as_str(), stats.posts, stats.words, stats.updated))
+            .collect()
+    }
+
+    #[test]
+    fn year_month_requires_valid_month() {
+        assert_eq!(year_month("2024-03-05 08:09:07"), Some((2024, 3)));
+        assert_eq!(year_month("2024-13-05 00:00:00"), None);
+        assert_eq!(year_month("2024"), None);
+    }
+
+    #[test]
+    fn continuous_periods_roll_over_years() {
+        assert_eq!(continuous_periods((2023, 11), (2024, 2), true), ["2023-11", "2023-12", "2024-01", "2024-02"]);
+        assert_eq!(continuous_periods((2022, 5), (2024, 1), false), ["2022", "2023", "2024"]);
+    }
+
+    #[test]
+    fn build_stats_counts_posts_and_updates() {
+        let posts = vec![
+            post(Some("2023-11-02 10:00:00"), None, 100, &["rust"]),
+            post(Some("2023-11-20 10:00:00"), Some("2024-01-03 10:00:00"), 200, &["rust", "hexo"]),
+            post(Some("2024-01-15 10:00:00"), None, 50, &["hexo"]),
+            post(None, None, 10, &["rust"]),
+        ];
+        let stats = build_stats(posts, true, 10);
+
+        assert_eq!(stats.total_posts, 4);
+        assert_eq!(stats.total_words, 360);
+        assert_eq!(stats.undated, 1);
+        assert_eq!(
+            periods(&stats.months),
+            [("2023-11", 2, 300, 0), ("2023-12", 0, 0, 0), ("2024-01", 1, 50, 1)]
+        );
+        assert_eq!(periods(&stats.years), [("2023", 2, 300, 0), ("2024", 1, 50, 1)]);
+        assert_eq!(stats.periods, ["2023-11", "2023-12", "2024-01"]);
+    }
+
+    #[test]
+    fn updated_extends_period_range() {
+        let posts = vec![post(Some("2024-01-15 10:00:00"), Some("2024-03-01 10:00:00"), 10, &[])];
+        let stats = build_stats(posts, true, 10);
+        assert_eq!(
+            periods(&stats.months),
+            [("2024-01", 1, 10, 0), ("2024-02", 0, 0, 0), ("2024-03", 0, 0, 1)]
+        );
+    }
+
+    #[test]
+    fn trends_keep_top_names_in_order() {
+        let posts = vec![
+            post(Some("2023-12-01 00:00:00"), None, 0, &["b", "c"]),
+            post(Some("2024-01-01 00:00:00"), None, 0, &["a", "b"]),
+            post(Some("2024-01-02 00:00:00"), None, 0, &["a", "b"]),
+            post(None, None, 0, &["c", "c2"]),
+        ];
+        let stats = build_stats(posts, false, 2);
+
+        assert_eq!(stats.periods, ["2023", "2024"]);
+        let trends: Vec<_> = stats
+            .tag_trends
+            .iter()
+            .map(|series| (series.name.as_str(), series.total, series.counts.clone()))
+            .collect();
+        // 没有日期的文章不计入，超出 top_n 的名称被丢弃
+        assert_eq!(trends, [("b", 3, vec![1, 2]), ("a", 2, vec![0, 2])]);
+        assert!(stats.category_trends.is_empty());
+    }
+}
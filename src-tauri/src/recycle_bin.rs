@@ -0,0 +1,491 @@
+// 回收站
+// 删除文章时不再直接 remove，而是移入回收站以便恢复：
+// Linux 上使用 freedesktop 回收站，其他平台或系统回收站不可用时使用应用数据目录下的回收站
+// 文章的资源文件夹（post_asset_folder）会随文章一起删除和恢复（全部恢复或全部不恢复）；
+// 系统回收站中的分组在删除时记录到应用数据目录的 trash-groups.json
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::dir_ops::move_path;
+use crate::error::{self, CommandError};
+#[cfg(target_os = "linux")]
+use crate::history::{read_json_if_exists, write_json};
+use crate::scope::PathScope;
+
+const SYSTEM_PREFIX: &str = "system:";
+const APP_PREFIX: &str = "app:";
+#[cfg(target_os = "linux")]
+const GROUPS_FILE: &str = "trash-groups.json";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashEntry {
+    // "system:<系统回收站 ID>" 或 "app:<应用回收站目录名>"
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    pub deleted_time: String,
+    pub is_directory: bool,
+    // 随该文件一起删除的路径（文章的资源文件夹）
+    pub related_paths: Vec<String>,
+}
+
+// 应用回收站中每个条目的 info.json
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AppTrashInfo {
+    // 第一个为主路径，其余为一并删除的路径；对应 files/<序号>
+    paths: Vec<PathBuf>,
+    deleted_time: String,
+    is_directory: bool,
+}
+
+// 一次删除中一起进入系统回收站的条目（系统回收站 ID，即 .trashinfo 路径），第一个为文章本身
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrashGroups {
+    groups: Vec<Vec<String>>,
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, CommandError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))
+}
+
+fn app_trash_dir(app_handle: &AppHandle) -> Result<PathBuf, CommandError> {
+    Ok(app_data_dir(app_handle)?.join("trash"))
+}
+
+// 文章对应的资源文件夹：与 .md 文件同目录、同名（去掉扩展名）的目录
+pub fn asset_folder_of(path: &Path) -> Option<PathBuf> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if ext != "md" && ext != "markdown" {
+        return None;
+    }
+    let folder = path.with_extension("");
+    if folder.is_dir() {
+        Some(folder)
+    } else {
+        None
+    }
+}
+
+// 移入应用回收站
+fn move_to_app_trash(app_handle: &AppHandle, paths: &[PathBuf]) -> Result<(), CommandError> {
+    let millis = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let trash_dir = app_trash_dir(app_handle)?;
+
+    let mut entry_dir = trash_dir.join(millis.to_string());
+    let mut suffix = 1;
+    while entry_dir.exists() {
+        entry_dir = trash_dir.join(format!("{}-{}", millis, suffix));
+        suffix += 1;
+    }
+    let files_dir = entry_dir.join("files");
+    fs::create_dir_all(&files_dir)?;
+
+    let info = AppTrashInfo {
+        paths: paths.to_vec(),
+        deleted_time: millis.to_string(),
+        is_directory: paths[0].is_dir(),
+    };
+    let info_json = serde_json::to_vec_pretty(&info).map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+    fs::write(entry_dir.join("info.json"), info_json)?;
+
+    for (index, path) in paths.iter().enumerate() {
+        if let Err(e) = move_path(path, &files_dir.join(index.to_string())) {
+            // 已移动的文件放回原处，避免文章和资源文件夹被拆散
+            for (moved_index, moved_path) in paths.iter().enumerate().take(index) {
+                let _ = move_path(&files_dir.join(moved_index.to_string()), moved_path);
+            }
+            let _ = fs::remove_dir_all(&entry_dir);
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+// 修改系统回收站分组记录（串行化读改写）
+#[cfg(target_os = "linux")]
+fn update_groups<T>(app_handle: &AppHandle, update: impl FnOnce(&mut TrashGroups) -> T) -> Result<T, CommandError> {
+    static LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let path = app_data_dir(app_handle)?.join(GROUPS_FILE);
+    // 分组记录损坏时报错，避免写回空记录覆盖掉原有分组
+    let mut groups: TrashGroups = read_json_if_exists(&path)?.unwrap_or_default();
+    let result = update(&mut groups);
+    write_json(&path, &groups)?;
+    Ok(result)
+}
+
+#[cfg(target_os = "linux")]
+fn read_groups(app_handle: &AppHandle) -> Result<TrashGroups, CommandError> {
+    Ok(read_json_if_exists(&app_data_dir(app_handle)?.join(GROUPS_FILE))?.unwrap_or_default())
+}
+
+#[cfg(target_os = "linux")]
+fn item_id(item: &trash::TrashItem) -> String {
+    item.id.to_string_lossy().to_string()
+}
+
+// 刚移入系统回收站的路径对应的条目 ID（同一路径取最近删除的一条）
+#[cfg(target_os = "linux")]
+fn trashed_ids(paths: &[PathBuf]) -> Result<Vec<String>, CommandError> {
+    let items = trash::os_limited::list().map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+    Ok(paths
+        .iter()
+        .filter_map(|path| {
+            items
+                .iter()
+                .filter(|item| &item.original_path() == path)
+                .max_by_key(|item| item.time_deleted)
+                .map(item_id)
+        })
+        .collect())
+}
+
+// 记录一起删除的条目，恢复和清空时按组处理
+#[cfg(target_os = "linux")]
+fn record_group(app_handle: &AppHandle, ids: Vec<String>) -> Result<(), CommandError> {
+    if ids.len() < 2 {
+        return Ok(());
+    }
+    update_groups(app_handle, |groups| groups.groups.push(ids))
+}
+
+// 移入系统回收站，失败时把已移走的路径放回原处
+#[cfg(target_os = "linux")]
+fn move_to_system_trash(app_handle: &AppHandle, paths: &[PathBuf]) -> Result<(), trash::Error> {
+    trash::delete_all(paths)?;
+    match trashed_ids(paths) {
+        Ok(ids) => {
+            if let Err(e) = record_group(app_handle, ids) {
+                log::warn!("记录回收站分组失败: {}", e);
+            }
+        }
+        Err(e) => log::warn!("读取系统回收站失败: {}", e),
+    }
+    Ok(())
+}
+
+// 将文件或目录（连同相关路径）移入回收站
+pub fn move_to_trash(app_handle: &AppHandle, paths: &[PathBuf]) -> Result<(), CommandError> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let Err(e) = move_to_system_trash(app_handle, paths) else {
+            return Ok(());
+        };
+        // 例如位于不支持回收站的挂载点，退回应用回收站
+        log::warn!("系统回收站不可用，使用应用回收站: {}", e);
+        // delete_all 可能已经移走了部分路径，先把它们恢复，保证文章和资源文件夹进入同一个回收站条目
+        let moved: Vec<PathBuf> = paths.iter().filter(|p| !p.exists()).cloned().collect();
+        if !moved.is_empty() {
+            let items: Vec<trash::TrashItem> = trash::os_limited::list()
+                .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+                .into_iter()
+                .filter(|item| moved.contains(&item.original_path()))
+                .collect();
+            trash::os_limited::restore_all(items).map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+        }
+        move_to_app_trash(app_handle, paths)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    move_to_app_trash(app_handle, paths)
+}
+
+// 应用回收站中属于已打开项目的条目（与系统回收站一样按原路径筛选）
+fn list_app_trash(scope: &PathScope, trash_dir: &Path) -> Result<Vec<TrashEntry>, CommandError> {
+    let mut entries = Vec::new();
+    let read_dir = match fs::read_dir(trash_dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(entries),
+        Err(e) => return Err(e.into()),
+    };
+
+    for entry in read_dir.flatten() {
+        let dir_name = entry.file_name().to_string_lossy().to_string();
+        let info: AppTrashInfo = match fs::read(entry.path().join("info.json"))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        {
+            Some(info) => info,
+            None => continue,
+        };
+        let Some(original) = info.paths.first() else {
+            continue;
+        };
+        if original.parent().is_none_or(|parent| scope.root_of(parent).is_none()) {
+            continue;
+        }
+
+        entries.push(TrashEntry {
+            id: format!("{}{}", APP_PREFIX, dir_name),
+            name: original
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            original_path: original.to_string_lossy().to_string(),
+            deleted_time: info.deleted_time.clone(),
+            is_directory: info.is_directory,
+            related_paths: info.paths[1..]
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        });
+    }
+    Ok(entries)
+}
+
+// 系统回收站中属于已打开项目的条目；删除时记录为一组的条目合并为一项（资源文件夹放在 related_paths）
+#[cfg(target_os = "linux")]
+fn list_system_trash(
+    scope: &PathScope,
+    app_handle: &AppHandle,
+) -> Result<Vec<(TrashEntry, Vec<trash::TrashItem>)>, CommandError> {
+    let items: Vec<trash::TrashItem> = trash::os_limited::list()
+        .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+        .into_iter()
+        .filter(|item| scope.root_of(&item.original_parent).is_some())
+        .collect();
+    let groups = read_groups(app_handle)?;
+
+    // 只有组内条目全部仍在回收站中时才作为一组；否则各自单独列出
+    let mut consumed = vec![false; items.len()];
+    let mut grouped: Vec<Vec<usize>> = Vec::new();
+    for group in &groups.groups {
+        let indexes: Vec<usize> = group
+            .iter()
+            .filter_map(|id| items.iter().position(|item| &item_id(item) == id))
+            .collect();
+        if indexes.len() == group.len() && indexes.iter().all(|index| !consumed[*index]) {
+            for index in &indexes {
+                consumed[*index] = true;
+            }
+            grouped.push(indexes);
+        }
+    }
+    grouped.extend((0..items.len()).filter(|index| !consumed[*index]).map(|index| vec![index]));
+
+    let entries = grouped
+        .into_iter()
+        .map(|indexes| {
+            let item = &items[indexes[0]];
+            let original_path = item.original_path();
+            let entry = TrashEntry {
+                id: format!("{}{}", SYSTEM_PREFIX, item.id.to_string_lossy()),
+                name: item.name.to_string_lossy().to_string(),
+                original_path: original_path.to_string_lossy().to_string(),
+                deleted_time: (item.time_deleted.max(0) as u64 * 1000).to_string(),
+                // 根据回收站中的元数据判断是否为目录
+                is_directory: trash::os_limited::metadata(item)
+                    .map(|m| matches!(m.size, trash::TrashItemSize::Entries(_)))
+                    .unwrap_or(false),
+                related_paths: indexes[1..]
+                    .iter()
+                    .map(|i| items[*i].original_path().to_string_lossy().to_string())
+                    .collect(),
+            };
+            (entry, indexes.into_iter().map(|i| items[i].clone()).collect())
+        })
+        .collect();
+    Ok(entries)
+}
+
+// 从分组记录中移除已恢复或已清空的条目
+#[cfg(target_os = "linux")]
+fn forget_items(app_handle: &AppHandle, items: &[trash::TrashItem]) -> Result<(), CommandError> {
+    let ids: Vec<String> = items.iter().map(item_id).collect();
+    update_groups(app_handle, |groups| {
+        groups.groups.retain(|group| !group.iter().any(|id| ids.contains(id)));
+    })
+}
+
+// 逐个恢复系统回收站条目；中途失败时把已恢复的重新移入回收站，保持整组在回收站中
+#[cfg(target_os = "linux")]
+fn restore_system_group(app_handle: &AppHandle, items: Vec<trash::TrashItem>) -> Result<(), CommandError> {
+    let mut restored: Vec<PathBuf> = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let path = item.original_path();
+        let Err(e) = trash::os_limited::restore_all([item.clone()]) else {
+            restored.push(path);
+            continue;
+        };
+        let e = CommandError::new(error::IO_ERROR, format!("恢复失败 {}: {}", path.display(), e));
+        if restored.is_empty() {
+            return Err(e);
+        }
+        if let Err(rollback) = trash::delete_all(&restored) {
+            let message = format!("{}；且以下文件未能放回回收站: {}", e.message, rollback);
+            return Err(CommandError::new(&e.code, message));
+        }
+        // 重新移入后条目 ID 已变化，更新分组记录
+        let mut ids = trashed_ids(&restored)?;
+        ids.extend(items[index..].iter().map(item_id));
+        forget_items(app_handle, &items)?;
+        record_group(app_handle, ids)?;
+        return Err(e);
+    }
+    forget_items(app_handle, &items)
+}
+
+fn ensure_restorable(scope: &PathScope, paths: &[PathBuf]) -> Result<(), CommandError> {
+    for path in paths {
+        scope.resolve(&path.to_string_lossy())?;
+        if path.exists() {
+            return Err(CommandError::new(
+                error::ALREADY_EXISTS,
+                format!("原位置已存在同名文件: {}", path.display()),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// 列出回收站中属于已打开项目的条目（按删除时间倒序）
+#[tauri::command]
+pub async fn list_trash(scope: State<'_, PathScope>, app_handle: AppHandle) -> Result<Vec<TrashEntry>, CommandError> {
+    let mut entries = list_app_trash(&scope, &app_trash_dir(&app_handle)?)?;
+
+    #[cfg(target_os = "linux")]
+    entries.extend(list_system_trash(&scope, &app_handle)?.into_iter().map(|(entry, _)| entry));
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.deleted_time.parse::<u128>().unwrap_or(0)));
+    Ok(entries)
+}
+
+// 从回收站恢复（连同资源文件夹），返回恢复后的路径
+#[tauri::command]
+pub async fn restore_from_trash(
+    id: String,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<String, CommandError> {
+    if let Some(dir_name) = id.strip_prefix(APP_PREFIX) {
+        if dir_name.contains(['/', '\\']) || dir_name.starts_with('.') {
+            return Err(CommandError::new(error::INVALID_PATH, format!("无效的回收站条目: {}", id)));
+        }
+        let entry_dir = app_trash_dir(&app_handle)?.join(dir_name);
+        let info: AppTrashInfo = serde_json::from_slice(&fs::read(entry_dir.join("info.json"))?)
+            .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+        ensure_restorable(&scope, &info.paths)?;
+
+        // 全部恢复或全部不恢复：中途失败时把已恢复的放回回收站
+        let files_dir = entry_dir.join("files");
+        for (index, path) in info.paths.iter().enumerate() {
+            if let Err(e) = move_path(&files_dir.join(index.to_string()), path) {
+                for (restored_index, restored) in info.paths.iter().enumerate().take(index) {
+                    if let Err(rollback) = move_path(restored, &files_dir.join(restored_index.to_string())) {
+                        log::warn!("恢复失败后放回回收站失败 {}: {}", restored.display(), rollback);
+                    }
+                }
+                return Err(e.into());
+            }
+        }
+        fs::remove_dir_all(&entry_dir)?;
+        return Ok(info.paths[0].to_string_lossy().to_string());
+    }
+
+    #[cfg(target_os = "linux")]
+    if id.starts_with(SYSTEM_PREFIX) {
+        let (entry, items) = list_system_trash(&scope, &app_handle)?
+            .into_iter()
+            .find(|(entry, _)| entry.id == id)
+            .ok_or_else(|| CommandError::new(error::NOT_FOUND, format!("回收站中不存在: {}", id)))?;
+        let paths: Vec<PathBuf> = items.iter().map(|item| item.original_path()).collect();
+        ensure_restorable(&scope, &paths)?;
+
+        restore_system_group(&app_handle, items)?;
+        return Ok(entry.original_path);
+    }
+
+    Err(CommandError::new(error::NOT_FOUND, format!("回收站中不存在: {}", id)))
+}
+
+// 清空回收站（只清理属于已打开项目的条目，应用回收站中没有 info.json 记录的目录不动），返回清理的条目数
+#[tauri::command]
+pub async fn empty_trash(scope: State<'_, PathScope>, app_handle: AppHandle) -> Result<usize, CommandError> {
+    let mut count = 0;
+
+    let trash_dir = app_trash_dir(&app_handle)?;
+    for entry in list_app_trash(&scope, &trash_dir)? {
+        let Some(dir_name) = entry.id.strip_prefix(APP_PREFIX) else {
+            continue;
+        };
+        fs::remove_dir_all(trash_dir.join(dir_name))?;
+        count += 1;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let groups = list_system_trash(&scope, &app_handle)?;
+        count += groups.len();
+        let items: Vec<trash::TrashItem> = groups.into_iter().flat_map(|(_, items)| items).collect();
+        forget_items(&app_handle, &items)?;
+        trash::os_limited::purge_all(items).map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_entry(trash_dir: &Path, dir_name: &str, paths: Vec<PathBuf>) {
+        let entry_dir = trash_dir.join(dir_name);
+        fs::create_dir_all(entry_dir.join("files")).unwrap();
+        let info = AppTrashInfo {
+            paths,
+            deleted_time: "1700000000000".to_string(),
+            is_directory: false,
+        };
+        fs::write(entry_dir.join("info.json"), serde_json::to_vec(&info).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn app_trash_lists_only_open_projects() {
+        let dir = tempfile::tempdir().unwrap();
+        let open = dir.path().join("blog");
+        let closed = dir.path().join("other-blog");
+        fs::create_dir_all(&open).unwrap();
+        fs::create_dir_all(&closed).unwrap();
+        let scope = PathScope::new();
+        let open = scope.add_root(&open).unwrap();
+        let closed = dunce::canonicalize(&closed).unwrap();
+
+        let trash_dir = dir.path().join("trash");
+        let post = open.join("source").join("_posts").join("a.md");
+        add_entry(&trash_dir, "1", vec![post.clone(), post.with_extension("")]);
+        add_entry(&trash_dir, "2", vec![closed.join("source").join("_posts").join("b.md")]);
+        // 没有 info.json 的目录不列出
+        fs::create_dir_all(trash_dir.join("3")).unwrap();
+
+        let entries = list_app_trash(&scope, &trash_dir).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, "app:1");
+        assert_eq!(entries[0].original_path, post.to_string_lossy());
+        assert_eq!(entries[0].related_paths, vec![post.with_extension("").to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn missing_app_trash_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_app_trash(&PathScope::new(), &dir.path().join("trash")).unwrap().is_empty());
+    }
+}
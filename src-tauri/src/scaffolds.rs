@@ -0,0 +1,395 @@
+// 模板（scaffolds）与新建文章
+// 读写 scaffolds/*.md，并在后端直接按模板生成文章，不必启动 Hexo 执行 hexo new。
+// 与 Hexo 一致：模板中的 {{ title }}、{{ date }}、{{ layout }} 和自定义变量会被替换，
+// 文件名由 slug 模块按项目设置生成，路径遵循 _config.yml 的 new_post_name、filename_case、default_layout，
+// post_asset_folder 开启时同时创建资源文件夹；目标已存在时在文件名（独立页面为目录名）后追加 -1、-2 ...
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use chrono::{Local, NaiveDateTime, Timelike};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::State;
+
+use crate::atomic_write::{self, WriteOptions};
+use crate::content_store::{self, ContentStore};
+use crate::drafts::{new_post_name, render_post_name, site_config, DATE_FORMAT};
+use crate::error::{self, CommandError};
+use crate::file_meta;
+use crate::front_matter::{parse_front_matter, update_front_matter_text};
+use crate::post_move::{is_markdown, normalize_lexically};
+use crate::scope::PathScope;
+use crate::slug::{make_slug, slugize, unique_slug, SlugService};
+use crate::FileInfo;
+
+// 模板文件不存在时使用 Hexo 内置的默认模板
+const DEFAULT_SCAFFOLDS: [(&str, &str); 4] = [
+    ("normal", "---\nlayout: {{ layout }}\ntitle: {{ title }}\ndate: {{ date }}\ntags:\n---\n"),
+    ("post", "---\ntitle: {{ title }}\ndate: {{ date }}\ntags:\n---\n"),
+    ("page", "---\ntitle: {{ title }}\ndate: {{ date }}\n---\n"),
+    ("draft", "---\ntitle: {{ title }}\ntags:\n---\n"),
+];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CreatePostOptions {
+    pub title: String,
+    // 不设置时使用 _config.yml 的 default_layout（默认 post）
+    pub layout: Option<String>,
+    // 文件名使用的 slug，不设置时按项目的 slug 设置由标题生成
+    pub slug: Option<String>,
+    // 标题的英文翻译，slug 设置为 ascii 时使用
+    pub translation: Option<String>,
+    // 模板中的自定义变量，例如 {{ author }}
+    pub variables: Map<String, Value>,
+    // 生成后再写入 front-matter 的字段（tags、categories、excerpt 等）
+    pub front_matter: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePostResult {
+    pub path: String,
+    pub layout: String,
+    pub asset_folder: Option<String>,
+    pub content: String,
+    pub hash: String,
+}
+
+// 模板名只能包含字母、数字、下划线和连字符
+fn scaffold_path(project_root: &Path, name: &str) -> Result<PathBuf, CommandError> {
+    let name = name.strip_suffix(".md").unwrap_or(name);
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, format!("无效的模板名: {}", name)));
+    }
+    Ok(project_root.join("scaffolds").join(format!("{}.md", name)))
+}
+
+fn default_scaffold(name: &str) -> Option<&'static str> {
+    DEFAULT_SCAFFOLDS
+        .iter()
+        .find(|(scaffold, _)| *scaffold == name)
+        .map(|(_, content)| *content)
+}
+
+// 与 Hexo 相同的查找顺序：scaffolds/<layout>.md、内置模板、scaffolds/normal.md、内置 normal 模板
+fn load_scaffold(project_root: &Path, layout: &str) -> Result<String, CommandError> {
+    for name in [layout, "normal"] {
+        let path = scaffold_path(project_root, name)?;
+        if path.is_file() {
+            return Ok(fs::read_to_string(&path)?);
+        }
+        if let Some(content) = default_scaffold(name) {
+            return Ok(content.to_string());
+        }
+    }
+    Ok(default_scaffold("normal").unwrap_or_default().to_string())
+}
+
+// 变量在 front-matter 中的写法：字符串按 YAML 需要加引号，其他值使用 JSON（即 YAML 流式写法）
+fn yaml_inline(value: &Value) -> String {
+    match value {
+        Value::String(text) => serde_yaml::to_string(text)
+            .map(|rendered| rendered.trim_end().to_string())
+            .unwrap_or_else(|_| text.clone()),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+// 替换模板变量；未定义的变量替换为空（与 Nunjucks 一致）。
+// front-matter 中未被引号包围的变量按 YAML 转义，避免标题中的冒号等字符破坏 front-matter
+fn render_scaffold(scaffold: &str, variables: &Map<String, Value>) -> String {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    let variable = VARIABLE.get_or_init(|| Regex::new(r"\{\{\s*([A-Za-z_]\w*)\s*\}\}").unwrap());
+
+    let front_matter_end = scaffold
+        .strip_prefix("---")
+        .and_then(|rest| rest.find("\n---"))
+        .map(|index| index + 3)
+        .unwrap_or(0);
+    variable
+        .replace_all(scaffold, |caps: &Captures| {
+            let value = variables.get(&caps[1]).cloned().unwrap_or(Value::Null);
+            let whole = caps.get(0).unwrap();
+            let quoted = scaffold[..whole.start()].ends_with(['"', '\'']);
+            if whole.start() < front_matter_end && !quoted {
+                yaml_inline(&value)
+            } else {
+                plain_text(&value)
+            }
+        })
+        .to_string()
+}
+
+// 目标已存在时追加 -1、-2 ...（同时避开已存在的同名资源文件夹）。
+// 独立页面 <slug>/index.md 给目录名追加序号，而不是在已有页面的目录中再生成 index-1.md
+fn ensure_unique(path: PathBuf, layout: &str) -> PathBuf {
+    if layout == "page" {
+        let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+            return path;
+        };
+        if !dir.exists() {
+            return path;
+        }
+        let dir_name = dir.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let mut index = 1;
+        loop {
+            let candidate = dir.with_file_name(format!("{}-{}", dir_name, index));
+            if !candidate.exists() {
+                return candidate.join(file_name);
+            }
+            index += 1;
+        }
+    }
+
+    let taken = |candidate: &Path| candidate.exists() || candidate.with_extension("").exists();
+    if !taken(&path) {
+        return path;
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_else(|| "md".to_string());
+    let mut index = 1;
+    loop {
+        let candidate = path.with_file_name(format!("{}-{}.{}", stem, index, extension));
+        if !taken(&candidate) {
+            return candidate;
+        }
+        index += 1;
+    }
+}
+
+// 按布局计算新文章的路径：page 为 source/<slug>/index.md，draft 为 source/_drafts/<slug>.md，
+// 其他布局为 source/_posts/<new_post_name>
+fn post_path(project_root: &Path, config: &serde_yaml::Value, layout: &str, slug: &str, date: &NaiveDateTime) -> Result<PathBuf, CommandError> {
+    let source = project_root.join("source");
+    let (base, relative) = match layout {
+        "page" => (source.clone(), format!("{}/index.md", slug)),
+        "draft" => (source.join("_drafts"), format!("{}.md", slug)),
+        _ => (source.join("_posts"), render_post_name(&new_post_name(config), slug, date)),
+    };
+    let path = normalize_lexically(&base.join(relative.trim_start_matches('/')));
+    if !path.starts_with(&base) || !is_markdown(&path) {
+        return Err(CommandError::new(error::INVALID_PATH, format!("生成了无效的文章路径: {}", relative)));
+    }
+    Ok(path)
+}
+
+// 列出 scaffolds 目录中的模板
+#[tauri::command]
+pub async fn list_scaffolds(project_path: String, scope: State<'_, PathScope>) -> Result<Vec<FileInfo>, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let Ok(entries) = fs::read_dir(project_root.join("scaffolds")) else {
+        return Ok(Vec::new());
+    };
+
+    let mut scaffolds: Vec<FileInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "md"))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let metadata = entry.metadata().ok().filter(|metadata| metadata.is_file())?;
+            Some(FileInfo {
+                name: path.file_stem()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                is_directory: false,
+                size: metadata.len(),
+                modified_time: file_meta::modified_time(&path),
+            })
+        })
+        .collect();
+    scaffolds.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scaffolds)
+}
+
+// 读取模板；文件不存在时返回 Hexo 的内置模板（没有对应的内置模板时报 NOT_FOUND）
+#[tauri::command]
+pub async fn read_scaffold(project_path: String, name: String, scope: State<'_, PathScope>) -> Result<String, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let path = scaffold_path(&project_root, &name)?;
+    if path.is_file() {
+        return Ok(fs::read_to_string(&path)?);
+    }
+    default_scaffold(name.strip_suffix(".md").unwrap_or(&name))
+        .map(str::to_string)
+        .ok_or_else(|| CommandError::new(error::NOT_FOUND, format!("模板不存在: {}", name)))
+}
+
+// 写入（或新建）模板
+#[tauri::command]
+pub async fn write_scaffold(
+    project_path: String,
+    name: String,
+    content: String,
+    scope: State<'_, PathScope>,
+) -> Result<String, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let path = scaffold_path(&project_root, &name)?;
+    atomic_write::write_atomic(&path, content.as_bytes(), WriteOptions::default())?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+// 按模板新建文章（代替 hexo new）
+#[tauri::command]
+pub async fn create_post(
+    project_path: String,
+    options: CreatePostOptions,
+    scope: State<'_, PathScope>,
+    store: State<'_, ContentStore>,
+    slugs: State<'_, SlugService>,
+) -> Result<CreatePostResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    if options.title.trim().is_empty() {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "标题不能为空"));
+    }
+
+    let config = site_config(&project_root);
+    let layout = options
+        .layout
+        .clone()
+        .or_else(|| config.get("default_layout").and_then(|v| v.as_str()).map(str::to_string))
+        .unwrap_or_else(|| "post".to_string());
+    let filename_case = config.get("filename_case").and_then(|v| v.as_i64()).unwrap_or(0);
+    let slug = match options.slug.as_deref() {
+        Some(slug) => slugize(slug, filename_case),
+        None => {
+            let base = make_slug(&slugs.settings(&project_root), &options.title, options.translation.as_deref(), filename_case);
+            unique_slug(&project_root, &base, None).slug
+        }
+    };
+    if slug.is_empty() {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "无法由标题生成文件名，请指定 slug"));
+    }
+
+    let date = Local::now().naive_local().with_nanosecond(0).unwrap_or_default();
+    let mut variables = options.variables.clone();
+    variables.insert("title".to_string(), Value::String(options.title.clone()));
+    variables.insert("date".to_string(), Value::String(date.format(DATE_FORMAT).to_string()));
+    variables.insert("layout".to_string(), Value::String(layout.clone()));
+
+    let mut content = render_scaffold(&load_scaffold(&project_root, &layout)?, &variables);
+    if options.front_matter.is_empty() {
+        parse_front_matter(&content)?;
+    } else {
+        content = update_front_matter_text(&content, &options.front_matter, &[])?;
+    }
+
+    let path = ensure_unique(post_path(&project_root, &config, &layout, &slug, &date)?, &layout);
+    atomic_write::write_atomic(&path, content.as_bytes(), WriteOptions::default())?;
+
+    // 独立页面的目录本身就是资源目录
+    let asset_folder = if layout != "page" && config.get("post_asset_folder").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let folder = path.with_extension("");
+        fs::create_dir_all(&folder)?;
+        Some(folder.to_string_lossy().to_string())
+    } else {
+        None
+    };
+
+    if let Err(e) = content_store::reindex(&store, &scope, &path) {
+        log::warn!("更新文章索引失败 {}: {}", path.display(), e);
+    }
+    Ok(CreatePostResult {
+        path: path.to_string_lossy().to_string(),
+        layout,
+        asset_folder,
+        hash: file_meta::content_hash(content.as_bytes()),
+        content,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+
+    fn variables(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    fn date() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, 5).unwrap().and_hms_opt(9, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn escapes_variables_only_inside_front_matter() {
+        let scaffold = "---\ntitle: {{ title }}\nquoted: \"{{ title }}\"\ntags: {{tags}}\nauthor: {{ author }}\n---\n# {{ title }}\n";
+        let rendered = render_scaffold(scaffold, &variables(json!({ "title": "Rust: 入门", "tags": ["a", "b"] })));
+        assert_eq!(
+            rendered,
+            "---\ntitle: 'Rust: 入门'\nquoted: \"Rust: 入门\"\ntags: [\"a\",\"b\"]\nauthor: \n---\n# Rust: 入门\n"
+        );
+        assert_eq!(parse_front_matter(&rendered).unwrap().data["title"], "Rust: 入门");
+    }
+
+    #[test]
+    fn scaffold_without_front_matter_is_plain_text() {
+        let rendered = render_scaffold("title: {{ title }}\n", &variables(json!({ "title": "a: b" })));
+        assert_eq!(rendered, "title: a: b\n");
+    }
+
+    #[test]
+    fn post_path_follows_layout() {
+        let root = Path::new("/blog");
+        let config: serde_yaml::Value = serde_yaml::from_str("new_post_name: :year/:month/:title").unwrap();
+        assert_eq!(
+            post_path(root, &config, "post", "hello", &date()).unwrap(),
+            Path::new("/blog/source/_posts/2024/03/hello.md")
+        );
+        assert_eq!(post_path(root, &config, "draft", "hello", &date()).unwrap(), Path::new("/blog/source/_drafts/hello.md"));
+        assert_eq!(post_path(root, &config, "page", "about", &date()).unwrap(), Path::new("/blog/source/about/index.md"));
+
+        let escaping: serde_yaml::Value = serde_yaml::from_str("new_post_name: ../../:title.md").unwrap();
+        assert!(post_path(root, &escaping, "post", "hello", &date()).is_err());
+        assert!(post_path(root, &config, "page", "../../etc", &date()).is_err());
+    }
+
+    #[test]
+    fn loads_scaffolds_in_hexo_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        // 没有 scaffolds 目录时使用内置模板
+        assert_eq!(load_scaffold(root, "page").unwrap(), default_scaffold("page").unwrap());
+        assert_eq!(load_scaffold(root, "custom").unwrap(), default_scaffold("normal").unwrap());
+
+        fs::create_dir_all(root.join("scaffolds")).unwrap();
+        fs::write(root.join("scaffolds").join("normal.md"), "normal").unwrap();
+        fs::write(root.join("scaffolds").join("post.md"), "post").unwrap();
+        assert_eq!(load_scaffold(root, "post").unwrap(), "post");
+        // 内置模板优先于 scaffolds/normal.md
+        assert_eq!(load_scaffold(root, "draft").unwrap(), default_scaffold("draft").unwrap());
+        assert_eq!(load_scaffold(root, "custom").unwrap(), "normal");
+        assert!(load_scaffold(root, "../post").is_err());
+    }
+
+    #[test]
+    fn unique_paths_avoid_existing_posts_and_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let posts = dir.path().join("source").join("_posts");
+        fs::create_dir_all(posts.join("b")).unwrap();
+        fs::write(posts.join("a.md"), "").unwrap();
+        assert_eq!(ensure_unique(posts.join("a.md"), "post"), posts.join("a-1.md"));
+        // 同名资源文件夹也算已占用
+        assert_eq!(ensure_unique(posts.join("b.md"), "post"), posts.join("b-1.md"));
+        assert_eq!(ensure_unique(posts.join("c.md"), "post"), posts.join("c.md"));
+
+        let source = dir.path().join("source");
+        fs::create_dir_all(source.join("about")).unwrap();
+        fs::create_dir_all(source.join("about-1")).unwrap();
+        fs::write(source.join("about").join("index.md"), "").unwrap();
+        assert_eq!(ensure_unique(source.join("about").join("index.md"), "page"), source.join("about-2").join("index.md"));
+        assert_eq!(ensure_unique(source.join("links").join("index.md"), "page"), source.join("links").join("index.md"));
+    }
+}
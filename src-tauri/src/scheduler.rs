@@ -0,0 +1,525 @@
+// 定时发布
+// 草稿的发布时间可以写在 front-matter 的 publish_at 中，也可以通过 schedule_post 保存在
+// 应用数据目录的 scheduler.json（不改动文章本身）。后台线程定期检查，到期后把草稿发布到
+// source/_posts，并按项目设置依次执行 hexo clean / generate / deploy。
+// 每一步都写入日志并通过 "scheduled-publish" 事件通知前端；应用未运行期间错过的发布在下次启动后补上。
+// 项目在打开（validate_hexo_project 注册项目目录）或设置发布时间后加入检查列表（保存在 scheduler.json），
+// 设置 enabled: false 可以停用。启动时重新注册列表中仍是 Hexo 项目的目录，以便补上错过的发布；
+// 本次运行中取消注册（unregister_project_root）的目录不再检查
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use chrono::{Local, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::content_store::ContentStore;
+use crate::drafts::{self, DATE_FORMAT};
+use crate::error::{self, CommandError};
+use crate::format_system_time;
+use crate::front_matter::parse_front_matter;
+use crate::history::{read_json, write_json, VersionHistory};
+use crate::post_index::{normalize_date, post_files, scalar_to_string};
+use crate::post_move::is_markdown;
+use crate::run_hexo_command;
+use crate::scope::{self, PathScope};
+use crate::text_encoding;
+
+pub const SCHEDULE_EVENT: &str = "scheduled-publish";
+// front-matter 中的发布时间键，发布时删除
+const PUBLISH_AT_KEY: &str = "publish_at";
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAX_LOG_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScheduleSettings {
+    pub enabled: bool,
+    // 发布后依次执行的命令
+    pub clean: bool,
+    pub generate: bool,
+    pub deploy: bool,
+}
+
+impl Default for ScheduleSettings {
+    fn default() -> Self {
+        ScheduleSettings {
+            enabled: true,
+            clean: false,
+            generate: false,
+            deploy: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ProjectSchedule {
+    settings: ScheduleSettings,
+    // 键为草稿相对于项目根目录的路径（使用 / 分隔），值为发布时间
+    entries: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleLogEntry {
+    // 毫秒时间戳字符串
+    pub time: String,
+    pub project_path: String,
+    // "published" | "failed" | "missing" | "command"
+    pub kind: String,
+    pub draft_path: Option<String>,
+    pub new_path: Option<String>,
+    pub publish_at: Option<String>,
+    // kind 为 "command" 时执行的命令（clean / generate / deploy）
+    pub command: Option<String>,
+    pub success: bool,
+    pub message: Option<String>,
+    // 发布时间早于本次启动，即应用未运行期间错过的发布
+    pub caught_up: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SchedulerState {
+    // 键为规范化后的项目根目录
+    projects: BTreeMap<String, ProjectSchedule>,
+    // 从旧到新
+    log: Vec<ScheduleLogEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPost {
+    pub path: String,
+    pub relative_path: String,
+    pub title: Option<String>,
+    pub publish_at: String,
+    // "frontMatter" | "sidecar"
+    pub source: &'static str,
+    pub due: bool,
+}
+
+pub struct Scheduler {
+    path: PathBuf,
+    state: Mutex<SchedulerState>,
+    started: NaiveDateTime,
+    // 本次运行中发布失败的草稿（项目, 相对路径），避免每次检查都重复失败；重新设置发布时间或重启后再试
+    failed: Mutex<HashSet<(String, String)>>,
+}
+
+fn now_local() -> NaiveDateTime {
+    Local::now().naive_local().with_nanosecond(0).unwrap_or_default()
+}
+
+fn parse_time(text: &str) -> Option<NaiveDateTime> {
+    normalize_date(text).and_then(|normalized| NaiveDateTime::parse_from_str(&normalized, DATE_FORMAT).ok())
+}
+
+fn relative_key(project_root: &Path, path: &Path) -> String {
+    path.strip_prefix(project_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+impl Scheduler {
+    pub fn open(path: PathBuf) -> Self {
+        let state = read_json(&path);
+        Scheduler {
+            path,
+            state: Mutex::new(state),
+            started: now_local(),
+            failed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn save(&self, state: &SchedulerState) -> Result<(), CommandError> {
+        write_json(&self.path, state)
+    }
+
+    // 确保项目在检查列表中
+    pub fn ensure_project(&self, project_root: &Path) -> Result<(), CommandError> {
+        let key = project_root.to_string_lossy().to_string();
+        let mut state = self.state.lock().unwrap();
+        if state.projects.contains_key(&key) {
+            return Ok(());
+        }
+        state.projects.insert(key, ProjectSchedule::default());
+        self.save(&state)
+    }
+
+    // 项目的设置，不在检查列表中时返回默认值
+    fn settings(&self, project_root: &Path) -> ScheduleSettings {
+        let state = self.state.lock().unwrap();
+        state
+            .projects
+            .get(project_root.to_string_lossy().as_ref())
+            .map(|project| project.settings.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_settings(&self, project_root: &Path, settings: ScheduleSettings) -> Result<(), CommandError> {
+        let mut state = self.state.lock().unwrap();
+        state
+            .projects
+            .entry(project_root.to_string_lossy().to_string())
+            .or_default()
+            .settings = settings;
+        self.save(&state)
+    }
+
+    fn set_entry(&self, project_root: &Path, relative: &str, publish_at: Option<String>) -> Result<(), CommandError> {
+        let project = project_root.to_string_lossy().to_string();
+        self.failed.lock().unwrap().remove(&(project.clone(), relative.to_string()));
+
+        let mut state = self.state.lock().unwrap();
+        let entries = &mut state.projects.entry(project).or_default().entries;
+        match publish_at {
+            Some(publish_at) => entries.insert(relative.to_string(), publish_at),
+            None => entries.remove(relative),
+        };
+        self.save(&state)
+    }
+
+    fn entries(&self, project_root: &Path) -> BTreeMap<String, String> {
+        let state = self.state.lock().unwrap();
+        state
+            .projects
+            .get(project_root.to_string_lossy().as_ref())
+            .map(|project| project.entries.clone())
+            .unwrap_or_default()
+    }
+
+    fn enabled_projects(&self) -> Vec<(PathBuf, ScheduleSettings)> {
+        let state = self.state.lock().unwrap();
+        state
+            .projects
+            .iter()
+            .filter(|(_, project)| project.settings.enabled)
+            .map(|(root, project)| (PathBuf::from(root), project.settings.clone()))
+            .collect()
+    }
+
+    // 写入日志并通知前端
+    fn record(&self, app_handle: &AppHandle, entry: ScheduleLogEntry) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.log.push(entry.clone());
+            let overflow = state.log.len().saturating_sub(MAX_LOG_ENTRIES);
+            state.log.drain(..overflow);
+            if let Err(e) = self.save(&state) {
+                log::warn!("保存定时发布日志失败: {}", e);
+            }
+        }
+        let _ = app_handle.emit(SCHEDULE_EVENT, &entry);
+    }
+}
+
+// 项目中所有设置了发布时间的草稿；schedule_post 保存的时间优先于 front-matter
+fn scheduled_posts(project_root: &Path, entries: &BTreeMap<String, String>, now: &NaiveDateTime) -> Vec<ScheduledPost> {
+    let drafts_dir = project_root.join("source").join("_drafts");
+    let mut posts: Vec<ScheduledPost> = post_files(project_root)
+        .into_iter()
+        .filter(|path| path.starts_with(&drafts_dir))
+        .filter_map(|path| {
+            let relative = relative_key(project_root, &path);
+            let data = fs::read(&path)
+                .ok()
+                .and_then(|bytes| text_encoding::decode(&bytes).ok())
+                .and_then(|decoded| parse_front_matter(&decoded.content).ok())
+                .map(|front_matter| front_matter.data)
+                .unwrap_or_default();
+            let (publish_at, source) = match entries.get(&relative) {
+                Some(publish_at) => (publish_at.clone(), "sidecar"),
+                None => {
+                    let publish_at = data.get(PUBLISH_AT_KEY).and_then(scalar_to_string)?;
+                    (parse_time(&publish_at)?.format(DATE_FORMAT).to_string(), "frontMatter")
+                }
+            };
+            let due = parse_time(&publish_at).is_some_and(|time| time <= *now);
+            Some(ScheduledPost {
+                path: path.to_string_lossy().to_string(),
+                relative_path: relative,
+                title: data.get("title").and_then(scalar_to_string),
+                publish_at,
+                source,
+                due,
+            })
+        })
+        .collect();
+    posts.sort_by(|a, b| a.publish_at.cmp(&b.publish_at));
+    posts
+}
+
+fn log_entry(project_root: &Path, kind: &str, success: bool) -> ScheduleLogEntry {
+    ScheduleLogEntry {
+        time: format_system_time(SystemTime::now()),
+        project_path: project_root.to_string_lossy().to_string(),
+        kind: kind.to_string(),
+        draft_path: None,
+        new_path: None,
+        publish_at: None,
+        command: None,
+        success,
+        message: None,
+        caught_up: false,
+    }
+}
+
+// 检查一个项目：发布到期的草稿，有文章发布时执行设置的命令
+fn run_project(app_handle: &AppHandle, scheduler: &Scheduler, project_root: &Path, settings: &ScheduleSettings) {
+    if !project_root.join("source").is_dir() {
+        return;
+    }
+    let project = project_root.to_string_lossy().to_string();
+    let entries = scheduler.entries(project_root);
+    let now = now_local();
+
+    // schedule_post 保存的草稿已被删除或移走
+    for relative in entries.keys() {
+        if !project_root.join(relative).is_file() {
+            let _ = scheduler.set_entry(project_root, relative, None);
+            let mut entry = log_entry(project_root, "missing", false);
+            entry.draft_path = Some(project_root.join(relative).to_string_lossy().to_string());
+            entry.publish_at = entries.get(relative).cloned();
+            entry.message = Some("草稿不存在，已取消定时发布".to_string());
+            scheduler.record(app_handle, entry);
+        }
+    }
+
+    let mut published = 0;
+    for post in scheduled_posts(project_root, &entries, &now) {
+        if !post.due || scheduler.failed.lock().unwrap().contains(&(project.clone(), post.relative_path.clone())) {
+            continue;
+        }
+        let Some(publish_at) = parse_time(&post.publish_at) else {
+            continue;
+        };
+
+        let mut entry = log_entry(project_root, "published", true);
+        entry.draft_path = Some(post.path.clone());
+        entry.publish_at = Some(post.publish_at.clone());
+        entry.caught_up = publish_at < scheduler.started;
+        let publish = drafts::publish(
+            &app_handle.state::<VersionHistory>(),
+            &app_handle.state::<PathScope>(),
+            &app_handle.state::<ContentStore>(),
+            project_root,
+            Path::new(&post.path),
+            &publish_at,
+            &[PUBLISH_AT_KEY.to_string()],
+        );
+        match publish {
+            Ok(result) => {
+                published += 1;
+                entry.new_path = Some(result.new_path);
+                if post.source == "sidecar" {
+                    let _ = scheduler.set_entry(project_root, &post.relative_path, None);
+                }
+            }
+            Err(e) => {
+                scheduler.failed.lock().unwrap().insert((project.clone(), post.relative_path.clone()));
+                entry.kind = "failed".to_string();
+                entry.success = false;
+                entry.message = Some(e.message);
+            }
+        }
+        scheduler.record(app_handle, entry);
+    }
+
+    if published == 0 {
+        return;
+    }
+    let commands = [("clean", settings.clean), ("generate", settings.generate), ("deploy", settings.deploy)];
+    for (command, _) in commands.iter().filter(|(_, enabled)| *enabled) {
+        let result = run_hexo_command(command, &project, &app_handle.state::<PathScope>());
+        let mut entry = log_entry(project_root, "command", result.result.success);
+        entry.command = Some(command.to_string());
+        entry.message = result.result.error.or(if result.result.success {
+            None
+        } else {
+            result.result.stderr
+        });
+        let success = entry.success;
+        scheduler.record(app_handle, entry);
+        // 前一步失败时不再继续（例如生成失败时不部署）
+        if !success {
+            break;
+        }
+    }
+}
+
+// 重新注册检查列表中启用的项目目录；已删除或不再是 Hexo 项目的目录跳过
+fn register_projects(app_handle: &AppHandle, scheduler: &Scheduler) {
+    for (project_root, _) in scheduler.enabled_projects() {
+        if !scope::is_hexo_project(&project_root) {
+            log::info!("跳过定时发布项目（不存在或不是 Hexo 项目）: {}", project_root.display());
+            continue;
+        }
+        if let Err(e) = scope::register_root(app_handle, &project_root) {
+            log::warn!("注册定时发布项目失败 {}: {}", project_root.display(), e);
+        }
+    }
+}
+
+// 启动后台检查线程；启动时先重新注册已保存的项目，第一次检查立即执行，补上错过的发布
+pub fn start(app_handle: AppHandle) {
+    register_projects(&app_handle, &app_handle.state::<Scheduler>());
+    std::thread::spawn(move || loop {
+        let scheduler = app_handle.state::<Scheduler>();
+        let scope = app_handle.state::<PathScope>();
+        for (project_root, settings) in scheduler.enabled_projects() {
+            if scope.root_of(&project_root).is_none() {
+                continue;
+            }
+            run_project(&app_handle, &scheduler, &project_root, &settings);
+        }
+        std::thread::sleep(CHECK_INTERVAL);
+    });
+}
+
+#[tauri::command]
+pub async fn get_schedule_settings(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<ScheduleSettings, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    Ok(scheduler.settings(&project_root))
+}
+
+#[tauri::command]
+pub async fn set_schedule_settings(
+    project_path: String,
+    settings: ScheduleSettings,
+    scope: State<'_, PathScope>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<ScheduleSettings, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    scheduler.set_settings(&project_root, settings.clone())?;
+    Ok(settings)
+}
+
+// 设置草稿的发布时间（publish_at 为 None 时取消），返回规范化后的时间
+#[tauri::command]
+pub async fn schedule_post(
+    project_path: String,
+    draft_path: String,
+    publish_at: Option<String>,
+    scope: State<'_, PathScope>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<Option<String>, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let draft = scope.resolve(&draft_path)?;
+    if !draft.starts_with(project_root.join("source").join("_drafts")) || !is_markdown(&draft) || !draft.is_file() {
+        return Err(CommandError::new(error::INVALID_PATH, "只能为 source/_drafts 中的 markdown 文件设置定时发布"));
+    }
+
+    let publish_at = match publish_at {
+        Some(text) => Some(
+            parse_time(&text)
+                .ok_or_else(|| CommandError::new(error::INVALID_ARGUMENT, format!("无法识别的时间: {}", text)))?
+                .format(DATE_FORMAT)
+                .to_string(),
+        ),
+        None => None,
+    };
+    scheduler.ensure_project(&project_root)?;
+    scheduler.set_entry(&project_root, &relative_key(&project_root, &draft), publish_at.clone())?;
+    Ok(publish_at)
+}
+
+// 列出项目中设置了发布时间的草稿（按发布时间排序）
+#[tauri::command]
+pub async fn list_scheduled_posts(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<Vec<ScheduledPost>, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let entries = scheduler.entries(&project_root);
+    Ok(scheduled_posts(&project_root, &entries, &now_local()))
+}
+
+// 定时发布日志（从新到旧）；指定 project_path 时只返回该项目的记录
+#[tauri::command]
+pub async fn get_schedule_log(
+    project_path: Option<String>,
+    limit: Option<usize>,
+    scope: State<'_, PathScope>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<Vec<ScheduleLogEntry>, CommandError> {
+    let project = match project_path {
+        Some(path) => Some(scope.resolve(&path)?.to_string_lossy().to_string()),
+        None => None,
+    };
+    let state = scheduler.state.lock().unwrap();
+    Ok(state
+        .log
+        .iter()
+        .rev()
+        .filter(|entry| project.as_ref().is_none_or(|project| &entry.project_path == project))
+        .take(limit.unwrap_or(100))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(text: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(text, DATE_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn parse_time_accepts_front_matter_formats() {
+        assert_eq!(parse_time("2024-03-05 08:09:07"), Some(time("2024-03-05 08:09:07")));
+        assert_eq!(parse_time("2024/3/5 8:09"), Some(time("2024-03-05 08:09:00")));
+        assert_eq!(parse_time("2024-03-05T08:09:07+08:00"), Some(time("2024-03-05 08:09:07")));
+        assert_eq!(parse_time("2024-03-05"), Some(time("2024-03-05 00:00:00")));
+        assert_eq!(parse_time("2024-13-05"), None);
+        assert_eq!(parse_time("tomorrow"), None);
+    }
+
+    #[test]
+    fn scheduled_posts_prefer_sidecar_and_mark_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let drafts_dir = root.join("source").join("_drafts");
+        fs::create_dir_all(&drafts_dir).unwrap();
+        fs::create_dir_all(root.join("source").join("_posts")).unwrap();
+        fs::write(drafts_dir.join("past.md"), "---\ntitle: Past\npublish_at: 2024-01-01 09:00\n---\n").unwrap();
+        fs::write(drafts_dir.join("future.md"), "---\ntitle: Future\npublish_at: 2024-12-01 09:00:00\n---\n").unwrap();
+        fs::write(drafts_dir.join("override.md"), "---\npublish_at: 2024-01-01 00:00:00\n---\n").unwrap();
+        fs::write(drafts_dir.join("invalid.md"), "---\npublish_at: someday\n---\n").unwrap();
+        fs::write(drafts_dir.join("plain.md"), "---\ntitle: Plain\n---\n").unwrap();
+        // 已发布的文章即使带有 publish_at 也不算定时发布
+        fs::write(root.join("source").join("_posts").join("done.md"), "---\npublish_at: 2024-01-01 00:00:00\n---\n").unwrap();
+
+        let mut entries = BTreeMap::new();
+        entries.insert("source/_drafts/override.md".to_string(), "2024-08-01 00:00:00".to_string());
+        entries.insert("source/_drafts/plain.md".to_string(), "2024-06-01 12:00:00".to_string());
+
+        let posts = scheduled_posts(root, &entries, &time("2024-06-01 12:00:00"));
+        let summary: Vec<_> = posts
+            .iter()
+            .map(|post| (post.relative_path.as_str(), post.publish_at.as_str(), post.source, post.due))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("source/_drafts/past.md", "2024-01-01 09:00:00", "frontMatter", true),
+                ("source/_drafts/plain.md", "2024-06-01 12:00:00", "sidecar", true),
+                ("source/_drafts/override.md", "2024-08-01 00:00:00", "sidecar", false),
+                ("source/_drafts/future.md", "2024-12-01 09:00:00", "frontMatter", false),
+            ]
+        );
+        assert_eq!(posts[0].title.as_deref(), Some("Past"));
+    }
+}
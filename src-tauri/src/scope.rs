@@ -0,0 +1,301 @@
+// 文件访问范围控制
+// 只允许文件命令访问已打开的 Hexo 项目目录和应用数据目录
+// 所有路径都会被规范化（解析符号链接），以阻止 ".." 穿越和符号链接逃逸
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::{self, CommandError};
+
+pub struct PathScope {
+    roots: Mutex<Vec<PathBuf>>,
+}
+
+impl PathScope {
+    pub fn new() -> Self {
+        PathScope {
+            roots: Mutex::new(Vec::new()),
+        }
+    }
+
+    // 注册一个允许访问的根目录，返回规范化后的路径
+    pub fn add_root(&self, path: &Path) -> Result<PathBuf, CommandError> {
+        let canonical = dunce::canonicalize(path)?;
+        if !canonical.is_dir() {
+            return Err(CommandError::new(
+                error::INVALID_PATH,
+                format!("不是目录: {}", path.display()),
+            ));
+        }
+
+        let mut roots = self.roots.lock().unwrap();
+        if !roots.contains(&canonical) {
+            roots.push(canonical.clone());
+        }
+        Ok(canonical)
+    }
+
+    pub fn remove_root(&self, path: &Path) -> bool {
+        let canonical = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut roots = self.roots.lock().unwrap();
+        let before = roots.len();
+        roots.retain(|root| root != &canonical);
+        roots.len() != before
+    }
+
+    // 返回包含该路径的已注册根目录
+    pub fn root_of(&self, path: &Path) -> Option<PathBuf> {
+        let roots = self.roots.lock().unwrap();
+        roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .cloned()
+    }
+
+    // 校验并规范化路径；目标可以尚不存在（写入新文件时），此时以最近的已存在祖先目录为准
+    pub fn resolve(&self, path: &str) -> Result<PathBuf, CommandError> {
+        let requested = Path::new(path);
+        if !requested.is_absolute() {
+            return Err(CommandError::new(
+                error::INVALID_PATH,
+                format!("必须使用绝对路径: {}", path),
+            ));
+        }
+        if requested.components().any(|c| matches!(c, Component::ParentDir)) {
+            return Err(out_of_scope(path));
+        }
+
+        let resolved = canonicalize_allow_missing(requested)?;
+        if self.root_of(&resolved).is_some() {
+            Ok(resolved)
+        } else {
+            Err(out_of_scope(path))
+        }
+    }
+}
+
+fn out_of_scope(path: &str) -> CommandError {
+    CommandError::new(
+        error::OUT_OF_SCOPE,
+        format!("路径不在已打开的项目范围内: {}", path),
+    )
+}
+
+// 规范化路径，允许末尾若干级尚不存在
+fn canonicalize_allow_missing(path: &Path) -> Result<PathBuf, CommandError> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+
+    loop {
+        match fs::symlink_metadata(existing) {
+            Ok(_) => break,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let name = existing.file_name().ok_or_else(|| CommandError::from(e))?;
+                missing.push(name.to_os_string());
+                existing = existing.parent().ok_or_else(|| {
+                    CommandError::new(error::INVALID_PATH, format!("无效路径: {}", path.display()))
+                })?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    let mut resolved = dunce::canonicalize(existing)?;
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
+// 判断目录是否为 Hexo 项目：package.json 的 dependencies 或 devDependencies 中有 hexo，并且存在 source 目录
+// 只有 _config.yml 不足以注册为访问范围，否则任意含有该文件的目录都会被放开
+pub fn is_hexo_project(path: &Path) -> bool {
+    let has_hexo_dependency = fs::read(path.join("package.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+        .is_some_and(|package| {
+            ["dependencies", "devDependencies"]
+                .iter()
+                .any(|key| package.get(key).and_then(|deps| deps.get("hexo")).is_some())
+        });
+    has_hexo_dependency && path.join("source").is_dir()
+}
+
+// 注册项目根目录，并同步放开 asset 协议对该目录的访问
+// 只在 Rust 端调用（validate_hexo_project 验证通过后、应用数据目录），webview 不能直接注册任意目录
+pub fn register_root(app_handle: &AppHandle, path: &Path) -> Result<PathBuf, CommandError> {
+    let canonical = dunce::canonicalize(path)?;
+    // 文件系统根目录和用户主目录范围过大，注册后等于放开所有文件
+    let is_home = app_handle.path().home_dir().ok().and_then(|home| dunce::canonicalize(home).ok()) == Some(canonical.clone());
+    if canonical.parent().is_none() || is_home {
+        return Err(CommandError::new(
+            error::INVALID_PATH,
+            format!("不能将根目录或用户主目录作为项目目录: {}", path.display()),
+        ));
+    }
+
+    let scope = app_handle.state::<PathScope>();
+    let root = scope.add_root(&canonical)?;
+    app_handle
+        .asset_protocol_scope()
+        .allow_directory(&root, true)
+        .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?;
+    Ok(root)
+}
+
+// 取消注册项目根目录
+// asset 协议的 forbid 规则无法撤销，会导致之后重新打开同一项目时图片无法加载，
+// 因此这里只收回文件命令的访问权限
+#[tauri::command]
+pub async fn unregister_project_root(path: String, scope: State<'_, PathScope>) -> Result<bool, CommandError> {
+    Ok(scope.remove_root(Path::new(&path)))
+}
+
+// 导入自定义背景图片：复制到应用数据目录的 backgrounds/ 下，返回复制后的路径
+// asset 协议只放开了应用数据目录，项目外的图片不会被直接暴露；已在 backgrounds/ 中的图片原样返回
+#[tauri::command]
+pub async fn import_background_image(path: String, app_handle: AppHandle) -> Result<String, CommandError> {
+    const IMAGE_EXTENSIONS: [&str; 8] = ["jpg", "jpeg", "png", "gif", "bmp", "webp", "svg", "avif"];
+
+    let canonical = dunce::canonicalize(&path)?;
+    let extension = canonical
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .filter(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()));
+    let Some(extension) = extension.filter(|_| canonical.is_file()) else {
+        return Err(CommandError::new(error::INVALID_PATH, format!("不是图片文件: {}", path)));
+    };
+
+    let backgrounds = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+        .join("backgrounds");
+    fs::create_dir_all(&backgrounds)?;
+    let backgrounds = dunce::canonicalize(&backgrounds)?;
+    if canonical.parent() == Some(backgrounds.as_path()) {
+        return Ok(canonical.to_string_lossy().to_string());
+    }
+
+    let bytes = fs::read(&canonical)?;
+    let hash = crate::file_meta::content_hash(&bytes);
+    let target = backgrounds.join(format!("{}.{}", &hash[..16], extension));
+    if !target.exists() {
+        crate::atomic_write::write_atomic(&target, &bytes, crate::atomic_write::WriteOptions::default())?;
+    }
+    // 只保留当前使用的背景图片
+    for entry in fs::read_dir(&backgrounds)?.flatten() {
+        if entry.path() != target {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope_with_root(root: &Path) -> PathScope {
+        let scope = PathScope::new();
+        scope.add_root(root).unwrap();
+        scope
+    }
+
+    fn assert_out_of_scope(scope: &PathScope, path: &Path) {
+        let Err(error) = scope.resolve(&path.to_string_lossy()) else {
+            panic!("{} should be out of scope", path.display());
+        };
+        assert_eq!(error.code, error::OUT_OF_SCOPE);
+    }
+
+    #[test]
+    fn rejects_parent_dir_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("blog");
+        fs::create_dir_all(root.join("source")).unwrap();
+        fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+        let scope = scope_with_root(&root);
+
+        assert_out_of_scope(&scope, &root.join("..").join("secret.txt"));
+        assert_out_of_scope(&scope, &root.join("source").join("..").join("source"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlink_escape() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("blog");
+        let outside = dir.path().join("outside");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        let scope = scope_with_root(&root);
+
+        assert_out_of_scope(&scope, &root.join("link").join("secret.txt"));
+        // 经过符号链接写入尚不存在的文件同样被拒绝
+        assert_out_of_scope(&scope, &root.join("link").join("new").join("file.md"));
+    }
+
+    #[test]
+    fn allows_missing_leaf_under_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("blog");
+        fs::create_dir_all(&root).unwrap();
+        let scope = scope_with_root(&root);
+
+        let resolved = scope.resolve(&root.join("source").join("_posts").join("new.md").to_string_lossy()).unwrap();
+        assert_eq!(resolved, dunce::canonicalize(&root).unwrap().join("source").join("_posts").join("new.md"));
+    }
+
+    #[test]
+    fn sibling_with_shared_prefix_is_out_of_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("blog");
+        let sibling = dir.path().join("blog2");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(&sibling).unwrap();
+        fs::write(sibling.join("post.md"), "post").unwrap();
+        let scope = scope_with_root(&root);
+
+        assert_out_of_scope(&scope, &sibling.join("post.md"));
+        assert_out_of_scope(&scope, &sibling);
+        assert!(scope.resolve(&root.to_string_lossy()).is_ok());
+    }
+
+    #[test]
+    fn relative_paths_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let scope = scope_with_root(dir.path());
+        let Err(error) = scope.resolve("source/_posts/a.md") else {
+            panic!("relative path should be rejected");
+        };
+        assert_eq!(error.code, error::INVALID_PATH);
+    }
+
+    #[test]
+    fn hexo_project_needs_dependency_and_source_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("_config.yml"), "title: Blog\n").unwrap();
+        assert!(!is_hexo_project(root));
+
+        fs::write(root.join("package.json"), r#"{"dependencies":{"hexo":"^7.0.0"}}"#).unwrap();
+        assert!(!is_hexo_project(root));
+
+        fs::create_dir(root.join("source")).unwrap();
+        assert!(is_hexo_project(root));
+
+        fs::write(root.join("package.json"), r#"{"name":"hexo-theme","dependencies":{"vue":"^3"}}"#).unwrap();
+        assert!(!is_hexo_project(root));
+
+        fs::write(root.join("package.json"), r#"{"devDependencies":{"hexo":"^7.0.0"}}"#).unwrap();
+        assert!(is_hexo_project(root));
+    }
+}
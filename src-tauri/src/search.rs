@@ -0,0 +1,293 @@
+// 全文搜索
+// 索引保存在内容数据库的 FTS5 表中。中日韩文字没有空格分词，这里先自行切分：
+// 连续的中日韩文字切成二元组（"全文搜索" -> "全文 文搜 搜索"），并额外索引最后一个字，
+// 这样单字查询用前缀匹配即可找到任意位置的字；其他文字按单词小写，
+// 再交给 FTS5 的 unicode61 分词器按空格建立索引。排序使用 bm25，标题权重最高
+
+use std::path::Path;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::content_store::ContentStore;
+use crate::error::{self, CommandError};
+use crate::post_index::PostSummary;
+use crate::scope::PathScope;
+use crate::word_count::is_cjk;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 200;
+// 摘要片段的长度（字符数）
+const SNIPPET_LENGTH: usize = 120;
+// 片段中第一个匹配之前保留的字符数
+const SNIPPET_LEAD: usize = 30;
+
+// 建立索引所需的原文
+#[derive(Debug, Clone, Default)]
+pub struct SearchDocument {
+    pub title: String,
+    // front-matter 原始文本（标签、分类、描述等都可以被搜到）
+    pub front_matter: String,
+    pub body: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub post: PostSummary,
+    // bm25 分数，越小越相关
+    pub score: f64,
+    // HTML 转义后的文本，匹配部分用 <mark> 包裹
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub hits: Vec<SearchHit>,
+    pub total: usize,
+}
+
+enum Segment {
+    Word(String),
+    Cjk(Vec<char>),
+}
+
+fn segments(text: &str) -> Vec<Segment> {
+    let mut result = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                result.push(Segment::Word(std::mem::take(&mut word)));
+            }
+            cjk.push(c);
+        } else if c.is_alphanumeric() {
+            if !cjk.is_empty() {
+                result.push(Segment::Cjk(std::mem::take(&mut cjk)));
+            }
+            word.extend(c.to_lowercase());
+        } else {
+            if !word.is_empty() {
+                result.push(Segment::Word(std::mem::take(&mut word)));
+            }
+            if !cjk.is_empty() {
+                result.push(Segment::Cjk(std::mem::take(&mut cjk)));
+            }
+        }
+    }
+    if !word.is_empty() {
+        result.push(Segment::Word(word));
+    }
+    if !cjk.is_empty() {
+        result.push(Segment::Cjk(cjk));
+    }
+    result
+}
+
+fn bigrams(chars: &[char]) -> Vec<String> {
+    if chars.len() == 1 {
+        return vec![chars[0].to_string()];
+    }
+    chars.windows(2).map(|pair| pair.iter().collect()).collect()
+}
+
+// 切分为以空格分隔的词元，写入 FTS5 表
+pub fn index_text(text: &str) -> String {
+    let mut tokens = Vec::new();
+    for segment in segments(text) {
+        match segment {
+            Segment::Word(word) => tokens.push(word),
+            Segment::Cjk(chars) => {
+                tokens.extend(bigrams(&chars));
+                // 最后一个字不是任何二元组的开头，单独索引
+                if chars.len() > 1 {
+                    tokens.extend(chars.last().map(char::to_string));
+                }
+            }
+        }
+    }
+    tokens.join(" ")
+}
+
+// 把用户输入转换为 FTS5 查询：所有词元都要出现；
+// 单个汉字（匹配以它开头的二元组或单独索引的字）和最后一个单词使用前缀匹配，方便边输入边搜索
+fn fts_query(query: &str) -> Option<String> {
+    let segments = segments(query);
+    let last = segments.len().checked_sub(1)?;
+    let mut terms = Vec::new();
+    for (index, segment) in segments.into_iter().enumerate() {
+        match segment {
+            Segment::Word(word) if index == last => terms.push(format!("\"{}\"*", word)),
+            Segment::Word(word) => terms.push(format!("\"{}\"", word)),
+            Segment::Cjk(chars) if chars.len() == 1 => terms.push(format!("\"{}\"*", chars[0])),
+            Segment::Cjk(chars) => terms.extend(bigrams(&chars).into_iter().map(|gram| format!("\"{}\"", gram))),
+        }
+    }
+    Some(terms.join(" "))
+}
+
+// 高亮时查找的词：单词和完整的中日韩词组
+fn highlight_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = segments(query)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Word(word) => word.chars().collect(),
+            Segment::Cjk(chars) => chars,
+        })
+        .collect();
+    // 先匹配较长的词，避免短词截断长词的高亮
+    terms.sort_by_key(|term| std::cmp::Reverse(term.len()));
+    terms
+}
+
+fn lowercase_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+// 找出所有匹配区间（字符下标，不重叠）
+fn find_matches(text: &[char], terms: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let lowered: Vec<char> = text.iter().map(|c| lowercase_char(*c)).collect();
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    for term in terms.iter().filter(|term| !term.is_empty()) {
+        let mut start = 0;
+        while start + term.len() <= lowered.len() {
+            if lowered[start..start + term.len()] == term[..] {
+                let end = start + term.len();
+                if !matches.iter().any(|(s, e)| start < *e && *s < end) {
+                    matches.push((start, end));
+                }
+                start = end;
+            } else {
+                start += 1;
+            }
+        }
+    }
+    matches.sort();
+    matches
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn highlight(text: &[char], matches: &[(usize, usize)], range: (usize, usize)) -> String {
+    let mut result = String::new();
+    let mut position = range.0;
+    for &(start, end) in matches.iter().filter(|(start, end)| *start >= range.0 && *end <= range.1) {
+        result.push_str(&escape_html(&text[position..start].iter().collect::<String>()));
+        result.push_str("<mark>");
+        result.push_str(&escape_html(&text[start..end].iter().collect::<String>()));
+        result.push_str("</mark>");
+        position = end;
+    }
+    result.push_str(&escape_html(&text[position..range.1].iter().collect::<String>()));
+    result
+}
+
+// 截取第一个匹配附近的片段；正文没有匹配（只命中标题或 front-matter）时取开头
+fn snippet(body: &str, terms: &[Vec<char>]) -> String {
+    let collapsed = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text: Vec<char> = collapsed.chars().collect();
+    let matches = find_matches(&text, terms);
+    let start = matches.first().map(|(start, _)| start.saturating_sub(SNIPPET_LEAD)).unwrap_or(0);
+    let end = (start + SNIPPET_LENGTH).min(text.len());
+
+    let mut result = String::new();
+    if start > 0 {
+        result.push('…');
+    }
+    result.push_str(&highlight(&text, &matches, (start, end)));
+    if end < text.len() {
+        result.push('…');
+    }
+    result
+}
+
+fn highlight_title(title: &str, terms: &[Vec<char>]) -> String {
+    let text: Vec<char> = title.chars().collect();
+    let matches = find_matches(&text, terms);
+    highlight(&text, &matches, (0, text.len()))
+}
+
+// 搜索项目中的文章（标题、front-matter 和正文）
+#[tauri::command]
+pub async fn search_posts(
+    project_path: String,
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<SearchResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let Some(match_query) = fts_query(&query) else {
+        return Ok(SearchResult {
+            hits: Vec::new(),
+            total: 0,
+        });
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = offset.unwrap_or(0);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        store.refresh(&project_root)?;
+        let (rows, total) = store.search(&project_root, Path::new(&project_path), &match_query, limit, offset)?;
+
+        let terms = highlight_terms(&query);
+        let hits = rows
+            .into_iter()
+            .map(|(post, score, document)| SearchHit {
+                title_highlight: highlight_title(&document.title, &terms),
+                snippet: snippet(&document.body, &terms),
+                post,
+                score,
+            })
+            .collect();
+        Ok(SearchResult { hits, total })
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn indexes_bigrams_and_the_last_character() {
+        assert_eq!(index_text("Rust 全文搜索"), "rust 全文 文搜 搜索 索");
+        assert_eq!(index_text("字"), "字");
+    }
+
+    #[test]
+    fn builds_prefix_queries() {
+        assert_eq!(fts_query("全文搜索").unwrap(), "\"全文\" \"文搜\" \"搜索\"");
+        assert_eq!(fts_query("索").unwrap(), "\"索\"*");
+        assert_eq!(fts_query("rust asy").unwrap(), "\"rust\" \"asy\"*");
+        assert!(fts_query(" ,. ").is_none());
+    }
+
+    #[test]
+    fn single_character_matches_anywhere_in_a_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("blog");
+        let posts = project.join("source").join("_posts");
+        fs::create_dir_all(&posts).unwrap();
+        fs::write(posts.join("a.md"), "---\ntitle: a\n---\n全文搜索\n").unwrap();
+        let store = ContentStore::open(&dir.path().join("index.db")).unwrap();
+        store.refresh(&project).unwrap();
+
+        for query in ["全", "文", "索"] {
+            let (_, total) = store.search(&project, &project, &fts_query(query).unwrap(), 10, 0).unwrap();
+            assert_eq!(total, 1, "{}", query);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+// 文章 slug（文件名 / URL）生成
+// 中文标题直接作为文件名时，URL 中全是百分号编码。这里提供几种生成方式：
+//   transliterate  逐字转写为拉丁字母，"你好 World" -> "ni-hao-world"（旧设置中的 pinyin 视为此模式）
+//   initials       转写结果的首字母，"你好 World" -> "nh-world"
+//   keep           与 Hexo 相同，保留原文，只替换特殊字符
+//   ascii          只保留 ASCII 字符（可以传入标题的英文翻译），为空时使用日期
+// 转写基于 deunicode 的逐字映射表，并不是真正的拼音转换：每个汉字只有一个固定读音，
+// 多音字不会按词语选择读音（"重庆" -> "zhong-qing"），日文汉字同样按汉语读音转写（假名按罗马字）。
+// 对读音有要求时请使用 ascii 模式并传入翻译或自行填写的 slug
+// 每个项目的设置保存在应用数据目录的 slug.json；生成时与已有文章比较，重名时追加 -1、-2 ...
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::Local;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::drafts::site_config;
+use crate::error::CommandError;
+use crate::history::{read_json, write_json};
+use crate::post_index::post_files;
+use crate::scope::PathScope;
+use crate::word_count::is_cjk;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlugMode {
+    #[serde(alias = "pinyin")]
+    Transliterate,
+    Initials,
+    #[default]
+    Keep,
+    Ascii,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SlugSettings {
+    pub mode: SlugMode,
+    // 超过长度时在 - 处截断，不设置则不限制
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlugResult {
+    pub slug: String,
+    // 处理重名之前的 slug
+    pub base: String,
+    // base 与已有文章重名
+    pub collided: bool,
+}
+
+pub struct SlugService {
+    path: PathBuf,
+    // 键为规范化后的项目根目录
+    settings: Mutex<BTreeMap<String, SlugSettings>>,
+}
+
+impl SlugService {
+    pub fn open(path: PathBuf) -> Self {
+        let settings = read_json(&path);
+        SlugService {
+            path,
+            settings: Mutex::new(settings),
+        }
+    }
+
+    pub fn settings(&self, project_root: &Path) -> SlugSettings {
+        let settings = self.settings.lock().unwrap();
+        settings
+            .get(project_root.to_string_lossy().as_ref())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn set_settings(&self, project_root: &Path, value: SlugSettings) -> Result<(), CommandError> {
+        let mut settings = self.settings.lock().unwrap();
+        settings.insert(project_root.to_string_lossy().to_string(), value);
+        write_json(&self.path, &*settings)
+    }
+}
+
+// Hexo（hexo-util）的 slugize：特殊字符和空白替换为 -，filename_case 为 1 时转小写，2 时转大写
+pub fn slugize(title: &str, filename_case: i64) -> String {
+    static SPECIAL: OnceLock<Regex> = OnceLock::new();
+    let special = SPECIAL.get_or_init(|| Regex::new(r#"[\s~`!@#$%^&*()\-_+=\[\]{}|\\;:"'<>,.?/]+"#).unwrap());
+    let cleaned: String = title.chars().filter(|c| !c.is_control()).collect();
+    let slug = special.replace_all(&cleaned, "-").trim_matches('-').to_string();
+    match filename_case {
+        1 => slug.to_lowercase(),
+        2 => slug.to_uppercase(),
+        _ => slug,
+    }
+}
+
+// 拆分为词：连续的 ASCII 字母数字为一个词，每个中日韩字符单独为一个词（按 deunicode 转写），其他字符作为分隔
+fn transliterated_words(text: &str) -> Vec<(String, bool)> {
+    let mut words: Vec<(String, bool)> = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        let ascii = if c.is_ascii() { c.to_string() } else { deunicode::deunicode_char(c).unwrap_or("").to_string() };
+        if is_cjk(c) {
+            if !word.is_empty() {
+                words.push((std::mem::take(&mut word), false));
+            }
+            let syllable = ascii.trim().to_lowercase();
+            if !syllable.is_empty() {
+                words.push((syllable, true));
+            }
+            continue;
+        }
+        // 带变音符号的拉丁字母转写后仍属于同一个词
+        for a in ascii.chars() {
+            if a.is_ascii_alphanumeric() {
+                word.push(a.to_ascii_lowercase());
+            } else if !word.is_empty() {
+                words.push((std::mem::take(&mut word), false));
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push((word, false));
+    }
+    words
+}
+
+fn transliterated_slug(title: &str) -> String {
+    let words: Vec<String> = transliterated_words(title).into_iter().map(|(word, _)| word).collect();
+    words.join("-")
+}
+
+// 连续中日韩字符转写后的首字母连在一起，其他单词保持不变
+fn initials_slug(title: &str) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut previous_cjk = false;
+    for (word, cjk) in transliterated_words(title) {
+        if cjk {
+            let initial = word.chars().next().map(String::from).unwrap_or_default();
+            match parts.last_mut() {
+                Some(last) if previous_cjk => last.push_str(&initial),
+                _ => parts.push(initial),
+            }
+        } else {
+            parts.push(word);
+        }
+        previous_cjk = cjk;
+    }
+    parts.join("-")
+}
+
+fn ascii_slug(text: &str) -> String {
+    let words: Vec<String> = transliterated_words(text)
+        .into_iter()
+        .filter(|(_, cjk)| !cjk)
+        .map(|(word, _)| word)
+        .collect();
+    words.join("-")
+}
+
+fn truncate(slug: &str, max_length: Option<usize>) -> String {
+    let Some(max_length) = max_length.filter(|max| *max > 0) else {
+        return slug.to_string();
+    };
+    if slug.chars().count() <= max_length {
+        return slug.to_string();
+    }
+    let cut: String = slug.chars().take(max_length).collect();
+    // 尽量在 - 处截断，避免截断单词
+    match cut.rfind('-') {
+        Some(index) if index > 0 => cut[..index].to_string(),
+        _ => cut.trim_end_matches('-').to_string(),
+    }
+}
+
+// 按设置生成 slug（不检查重名）；translation 只在 ascii 模式下使用
+pub fn make_slug(settings: &SlugSettings, title: &str, translation: Option<&str>, filename_case: i64) -> String {
+    let slug = match settings.mode {
+        SlugMode::Transliterate => transliterated_slug(title),
+        SlugMode::Initials => initials_slug(title),
+        SlugMode::Keep => slugize(title, filename_case),
+        SlugMode::Ascii => ascii_slug(translation.filter(|t| !t.trim().is_empty()).unwrap_or(title)),
+    };
+    let slug = truncate(&slug, settings.max_length);
+    if slug.is_empty() && settings.mode == SlugMode::Ascii {
+        return format!("post-{}", Local::now().format("%Y%m%d%H%M%S"));
+    }
+    slug
+}
+
+// 项目中已有文章的文件名（不含扩展名，小写），exclude 为正在重命名的文章本身
+fn existing_slugs(project_root: &Path, exclude: Option<&Path>) -> HashSet<String> {
+    post_files(project_root)
+        .into_iter()
+        .filter(|path| Some(path.as_path()) != exclude)
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_lowercase()))
+        .collect()
+}
+
+// 与已有文章重名时追加 -1、-2 ...
+pub fn unique_slug(project_root: &Path, base: &str, exclude: Option<&Path>) -> SlugResult {
+    let existing = existing_slugs(project_root, exclude);
+    let collided = existing.contains(&base.to_lowercase());
+    let mut slug = base.to_string();
+    let mut index = 1;
+    while existing.contains(&slug.to_lowercase()) {
+        slug = format!("{}-{}", base, index);
+        index += 1;
+    }
+    SlugResult {
+        slug,
+        base: base.to_string(),
+        collided,
+    }
+}
+
+#[tauri::command]
+pub async fn get_slug_settings(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    slugs: State<'_, SlugService>,
+) -> Result<SlugSettings, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    Ok(slugs.settings(&project_root))
+}
+
+#[tauri::command]
+pub async fn set_slug_settings(
+    project_path: String,
+    settings: SlugSettings,
+    scope: State<'_, PathScope>,
+    slugs: State<'_, SlugService>,
+) -> Result<SlugSettings, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    slugs.set_settings(&project_root, settings.clone())?;
+    Ok(settings)
+}
+
+// 由标题生成 slug 并检查重名；mode 不设置时使用项目设置，
+// 重命名文章时传入 exclude_path，避免与文章自身冲突
+#[tauri::command]
+pub async fn generate_slug(
+    project_path: String,
+    title: String,
+    mode: Option<SlugMode>,
+    translation: Option<String>,
+    exclude_path: Option<String>,
+    scope: State<'_, PathScope>,
+    slugs: State<'_, SlugService>,
+) -> Result<SlugResult, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    let exclude = match exclude_path {
+        Some(path) => Some(scope.resolve(&path)?),
+        None => None,
+    };
+
+    let mut settings = slugs.settings(&project_root);
+    if let Some(mode) = mode {
+        settings.mode = mode;
+    }
+    let filename_case = site_config(&project_root)
+        .get("filename_case")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    let base = make_slug(&settings, &title, translation.as_deref(), filename_case);
+    Ok(unique_slug(&project_root, &base, exclude.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(mode: SlugMode) -> SlugSettings {
+        SlugSettings { mode, max_length: None }
+    }
+
+    #[test]
+    fn transliterates_per_character() {
+        assert_eq!(make_slug(&settings(SlugMode::Transliterate), "你好 World", None, 0), "ni-hao-world");
+        assert_eq!(make_slug(&settings(SlugMode::Initials), "你好 World", None, 0), "nh-world");
+        // 多音字不按词语选择读音
+        assert_eq!(make_slug(&settings(SlugMode::Transliterate), "重庆", None, 0), "zhong-qing");
+    }
+
+    #[test]
+    fn reads_legacy_pinyin_setting() {
+        let settings: SlugSettings = serde_json::from_str(r#"{ "mode": "pinyin" }"#).unwrap();
+        assert_eq!(settings.mode, SlugMode::Transliterate);
+        assert_eq!(serde_json::to_value(settings.mode).unwrap(), "transliterate");
+    }
+
+    #[test]
+    fn keep_matches_hexo_and_truncates_at_dash() {
+        assert_eq!(make_slug(&settings(SlugMode::Keep), "Hello, 世界!", None, 1), "hello-世界");
+        let limited = SlugSettings {
+            mode: SlugMode::Transliterate,
+            max_length: Some(8),
+        };
+        assert_eq!(make_slug(&limited, "你好 World", None, 0), "ni-hao");
+    }
+}
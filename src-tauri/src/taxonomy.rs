@@ -0,0 +1,484 @@
+// 标签 / 分类批量重构
+// 重命名、合并、删除标签或分类时，先在内存中计算所有文章的改写结果（dry_run 时直接返回预览），
+// 再逐个原子写入；任何一篇写入失败都会把已写入的文章恢复原样，保证博客不会停在中间状态。
+// 另外提供分类树：categories: [A, B] 是层级路径 A > B，[[A], [B]] 是两个并列分类
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, State};
+
+use crate::atomic_write::{self, WriteOptions};
+use crate::content_store::{self, ContentStore};
+use crate::error::{self, CommandError};
+use crate::file_meta;
+use crate::front_matter::{parse_front_matter, update_front_matter_text, FrontMatterFormat};
+use crate::history::VersionHistory;
+use crate::post_index::{category_paths, post_files, tag_list};
+use crate::save_file;
+use crate::scope::PathScope;
+use crate::text_encoding;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaxonomyKind {
+    Tag,
+    Category,
+}
+
+impl TaxonomyKind {
+    fn key(self) -> &'static str {
+        match self {
+            TaxonomyKind::Tag => "tags",
+            TaxonomyKind::Category => "categories",
+        }
+    }
+}
+
+// 名称以路径表示：标签只有一级；分类为从顶级分类开始的各级名称，例如 [技术, Rust]，
+// 操作会作用于该分类及其所有子分类
+enum Operation {
+    Merge { sources: Vec<Vec<String>>, target: Vec<String> },
+    Delete { name: Vec<String> },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedPost {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedPost {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaxonomyResult {
+    pub affected: Vec<AffectedPost>,
+    // front-matter 无法解析、未做检查的文章
+    pub skipped: Vec<SkippedPost>,
+    // dry_run 时为 false
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryNode {
+    pub name: String,
+    // 从顶级分类开始的各级名称（分类名中可能含有 /，因此不拼接成字符串）
+    pub path: Vec<String>,
+    // 该分类及其子分类中的文章数（同一篇文章只计一次）
+    pub count: usize,
+    // 直接归属该分类的文章数
+    pub direct_count: usize,
+    pub children: Vec<CategoryNode>,
+}
+
+struct PlannedWrite {
+    path: PathBuf,
+    original: Vec<u8>,
+    updated: Vec<u8>,
+}
+
+// 去掉各级名称两端的空白，并忽略空的层级
+fn split_path<'a>(parts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn dedupe<T: PartialEq>(items: Vec<T>) -> Vec<T> {
+    let mut result: Vec<T> = Vec::with_capacity(items.len());
+    for item in items {
+        if !result.contains(&item) {
+            result.push(item);
+        }
+    }
+    result
+}
+
+fn apply_to_tags(tags: &[String], operation: &Operation) -> Vec<String> {
+    let is_tag = |path: &[String], tag: &String| path == std::slice::from_ref(tag);
+    let mapped = tags.iter().filter_map(|tag| match operation {
+        Operation::Merge { sources, target } if sources.iter().any(|source| is_tag(source, tag)) => Some(target.concat()),
+        Operation::Delete { name } if is_tag(name, tag) => None,
+        _ => Some(tag.clone()),
+    });
+    dedupe(mapped.collect())
+}
+
+fn apply_to_categories(paths: &[Vec<String>], operation: &Operation) -> Vec<Vec<String>> {
+    let mapped = paths.iter().filter_map(|path| match operation {
+        Operation::Merge { sources, target } => {
+            let mapped = sources.iter().find_map(|source| {
+                path.starts_with(source)
+                    .then(|| target.iter().chain(&path[source.len()..]).cloned().collect())
+            });
+            Some(mapped.unwrap_or_else(|| path.clone()))
+        }
+        // 删除分类时保留其上级分类
+        Operation::Delete { name: prefix } => {
+            if path.starts_with(prefix) {
+                let parent = path[..prefix.len() - 1].to_vec();
+                (!parent.is_empty()).then_some(parent)
+            } else {
+                Some(path.clone())
+            }
+        }
+    });
+    dedupe(mapped.collect())
+}
+
+// 按 Hexo 的规则写回 categories：单条路径写成平铺列表，多条路径写成列表的列表
+fn categories_value(paths: &[Vec<String>], original: Option<&Value>) -> Value {
+    match paths {
+        [] => Value::Array(Vec::new()),
+        [path] if path.len() == 1 && matches!(original, Some(Value::String(_))) => Value::String(path[0].clone()),
+        [path] => Value::from(path.clone()),
+        paths => Value::Array(paths.iter().map(|path| Value::from(path.clone())).collect()),
+    }
+}
+
+fn tags_value(tags: &[String], original: Option<&Value>) -> Value {
+    match tags {
+        [tag] if matches!(original, Some(Value::String(_))) => Value::String(tag.clone()),
+        tags => Value::from(tags.to_vec()),
+    }
+}
+
+fn plan(
+    project_root: &Path,
+    display_root: &Path,
+    kind: TaxonomyKind,
+    operation: &Operation,
+) -> (Vec<PlannedWrite>, TaxonomyResult) {
+    let mut writes = Vec::new();
+    let mut result = TaxonomyResult {
+        affected: Vec::new(),
+        skipped: Vec::new(),
+        applied: false,
+    };
+
+    for path in post_files(project_root) {
+        let display = path
+            .strip_prefix(project_root)
+            .map(|relative| display_root.join(relative))
+            .unwrap_or_else(|_| path.clone())
+            .to_string_lossy()
+            .to_string();
+        let planned = fs::read(&path).map_err(CommandError::from).and_then(|original| {
+            let decoded = text_encoding::decode(&original)?;
+            let front_matter = parse_front_matter(&decoded.content)?;
+            if front_matter.format == FrontMatterFormat::None {
+                return Ok(None);
+            }
+
+            let current = front_matter.data.get(kind.key());
+            let updated = match kind {
+                TaxonomyKind::Tag => {
+                    let tags = tag_list(current);
+                    let new_tags = apply_to_tags(&tags, operation);
+                    (new_tags != tags).then(|| tags_value(&new_tags, current))
+                }
+                TaxonomyKind::Category => {
+                    let paths = category_paths(current);
+                    let new_paths = apply_to_categories(&paths, operation);
+                    (new_paths != paths).then(|| categories_value(&new_paths, current))
+                }
+            };
+            let Some(updated) = updated else {
+                return Ok(None);
+            };
+
+            let mut updates = Map::new();
+            updates.insert(kind.key().to_string(), updated.clone());
+            let text = update_front_matter_text(&decoded.content, &updates, &[])?;
+            let bytes = text_encoding::encode(&text, decoded.encoding, decoded.line_ending);
+            Ok(Some((
+                AffectedPost {
+                    path: display.clone(),
+                    before: current.cloned().unwrap_or(Value::Null),
+                    after: updated,
+                },
+                PlannedWrite {
+                    path: path.clone(),
+                    original,
+                    updated: bytes,
+                },
+            )))
+        });
+
+        match planned {
+            Ok(Some((affected, write))) => {
+                result.affected.push(affected);
+                writes.push(write);
+            }
+            Ok(None) => {}
+            Err(e) => result.skipped.push(SkippedPost {
+                path: display,
+                error: e.message,
+            }),
+        }
+    }
+    (writes, result)
+}
+
+// 依次通过 save_file 写入所有改动（保存版本历史、检查冲突并更新索引）；失败时把已写入的文件恢复为原内容
+fn apply(writes: &[PlannedWrite], history: &VersionHistory, scope: &PathScope, store: &ContentStore) -> Result<(), CommandError> {
+    let _guard = file_meta::write_lock();
+    let mut written: Vec<&PlannedWrite> = Vec::new();
+    for write in writes {
+        // 计算改写后文件又被外部修改过，save_file 会在重命名覆盖前发现并放弃整个操作
+        let Err(e) = save_file(history, scope, store, &write.path, Some(&write.original), &write.updated, WriteOptions::default()) else {
+            written.push(write);
+            continue;
+        };
+
+        let mut failed_rollbacks = Vec::new();
+        for write in written.iter().rev() {
+            if atomic_write::write_atomic(&write.path, &write.original, WriteOptions::default()).is_err() {
+                failed_rollbacks.push(write.path.to_string_lossy().to_string());
+            }
+            let _ = content_store::reindex(store, scope, &write.path);
+        }
+        if failed_rollbacks.is_empty() {
+            return Err(e);
+        }
+        let message = format!("{}；且以下文件未能恢复: {}", e.message, failed_rollbacks.join(", "));
+        return Err(CommandError::new(&e.code, message).with_details(serde_json::json!({ "unrestored": failed_rollbacks })));
+    }
+    Ok(())
+}
+
+fn run(
+    project_path: &str,
+    kind: TaxonomyKind,
+    operation: Operation,
+    dry_run: bool,
+    scope: &PathScope,
+    history: &VersionHistory,
+    store: &ContentStore,
+) -> Result<TaxonomyResult, CommandError> {
+    let project_root = scope.resolve(project_path)?;
+    let (writes, mut result) = plan(&project_root, Path::new(project_path), kind, &operation);
+    if dry_run || writes.is_empty() {
+        return Ok(result);
+    }
+
+    apply(&writes, history, scope, store)?;
+    result.applied = true;
+    Ok(result)
+}
+
+// 命令参数中的名称为各级名称（与 category_tree 返回的 path 相同，名称中可以含有 /），标签只有一级
+fn require_name(kind: TaxonomyKind, name: &[String]) -> Result<Vec<String>, CommandError> {
+    let path = split_path(name.iter().map(String::as_str));
+    if path.is_empty() {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "名称不能为空"));
+    }
+    if kind == TaxonomyKind::Tag && path.len() > 1 {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "标签名称只能有一级"));
+    }
+    Ok(path)
+}
+
+// 重命名标签或分类
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn rename_taxonomy(
+    project_path: String,
+    kind: TaxonomyKind,
+    from: Vec<String>,
+    to: Vec<String>,
+    dry_run: Option<bool>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<TaxonomyResult, CommandError> {
+    let operation = Operation::Merge {
+        sources: vec![require_name(kind, &from)?],
+        target: require_name(kind, &to)?,
+    };
+    run(&project_path, kind, operation, dry_run.unwrap_or(false), &scope, &history, &store)
+}
+
+// 将多个标签或分类合并为一个
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn merge_taxonomy(
+    project_path: String,
+    kind: TaxonomyKind,
+    sources: Vec<Vec<String>>,
+    target: Vec<String>,
+    dry_run: Option<bool>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<TaxonomyResult, CommandError> {
+    let sources = sources.iter().map(|source| require_name(kind, source)).collect::<Result<Vec<_>, _>>()?;
+    if sources.is_empty() {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "至少需要一个要合并的名称"));
+    }
+    let operation = Operation::Merge {
+        sources,
+        target: require_name(kind, &target)?,
+    };
+    run(&project_path, kind, operation, dry_run.unwrap_or(false), &scope, &history, &store)
+}
+
+// 从所有文章中删除标签或分类
+#[tauri::command]
+pub async fn delete_taxonomy(
+    project_path: String,
+    kind: TaxonomyKind,
+    name: Vec<String>,
+    dry_run: Option<bool>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<TaxonomyResult, CommandError> {
+    let operation = Operation::Delete {
+        name: require_name(kind, &name)?,
+    };
+    run(&project_path, kind, operation, dry_run.unwrap_or(false), &scope, &history, &store)
+}
+
+// 把一条分类路径插入树中；parent_path 为当前层级的上级路径
+fn insert_node(nodes: &mut Vec<CategoryNode>, parent_path: &[String], parts: &[String], count: usize, direct_count: usize) {
+    let Some((name, rest)) = parts.split_first() else {
+        return;
+    };
+    let path: Vec<String> = parent_path.iter().chain(std::iter::once(name)).cloned().collect();
+    let index = match nodes.iter().position(|node| &node.name == name) {
+        Some(index) => index,
+        None => {
+            nodes.push(CategoryNode {
+                name: name.clone(),
+                path: path.clone(),
+                count: 0,
+                direct_count: 0,
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        }
+    };
+    if rest.is_empty() {
+        nodes[index].count = count;
+        nodes[index].direct_count = direct_count;
+    } else {
+        insert_node(&mut nodes[index].children, &path, rest, count, direct_count);
+    }
+}
+
+// 返回项目的分类树（同级按名称排序）
+#[tauri::command]
+pub async fn category_tree(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    app_handle: AppHandle,
+) -> Result<Vec<CategoryNode>, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let store = app_handle.state::<ContentStore>();
+        store.refresh(&project_root)?;
+
+        let mut tree = Vec::new();
+        // 按路径排序，父分类总是先于子分类插入
+        for (path, count, direct_count) in store.category_counts(&project_root)? {
+            insert_node(&mut tree, &[], &path, count, direct_count);
+        }
+        Ok(tree)
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+// 把分类（连同子分类）移动到另一个分类下；new_parent 为空时移动为顶级分类
+#[tauri::command]
+pub async fn move_category(
+    project_path: String,
+    path: Vec<String>,
+    new_parent: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    scope: State<'_, PathScope>,
+    history: State<'_, VersionHistory>,
+    store: State<'_, ContentStore>,
+) -> Result<TaxonomyResult, CommandError> {
+    let source = require_name(TaxonomyKind::Category, &path)?;
+    let parent = new_parent.as_deref().map(|parent| split_path(parent.iter().map(String::as_str))).unwrap_or_default();
+    if parent.starts_with(&source) {
+        return Err(CommandError::new(error::INVALID_ARGUMENT, "不能将分类移动到其自身或子分类下"));
+    }
+
+    let name = source.last().cloned().unwrap_or_default();
+    let target: Vec<String> = parent.into_iter().chain(std::iter::once(name)).collect();
+    let operation = Operation::Merge {
+        sources: vec![source],
+        target,
+    };
+    run(&project_path, TaxonomyKind::Category, operation, dry_run.unwrap_or(false), &scope, &history, &store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(parts: &[&str]) -> Vec<String> {
+        parts.iter().map(|part| part.to_string()).collect()
+    }
+
+    #[test]
+    fn merges_category_with_children() {
+        let operation = Operation::Merge {
+            sources: vec![path(&["技术"])],
+            target: path(&["编程", "技术"]),
+        };
+        let paths = vec![path(&["技术", "Rust"]), path(&["技术/Rust"]), path(&["随笔"])];
+        assert_eq!(
+            apply_to_categories(&paths, &operation),
+            vec![path(&["编程", "技术", "Rust"]), path(&["技术/Rust"]), path(&["随笔"])]
+        );
+    }
+
+    #[test]
+    fn deleting_a_category_keeps_its_parent() {
+        let operation = Operation::Delete { name: path(&["技术", "Rust"]) };
+        let paths = vec![path(&["技术", "Rust", "async"]), path(&["技术", "Go"])];
+        assert_eq!(apply_to_categories(&paths, &operation), vec![path(&["技术"]), path(&["技术", "Go"])]);
+    }
+
+    #[test]
+    fn names_are_passed_as_name_lists() {
+        assert_eq!(require_name(TaxonomyKind::Tag, &path(&[" a/b "])).unwrap(), path(&["a/b"]));
+        assert_eq!(require_name(TaxonomyKind::Category, &path(&["A/B"])).unwrap(), path(&["A/B"]));
+        assert_eq!(require_name(TaxonomyKind::Category, &path(&["a ", "", "b"])).unwrap(), path(&["a", "b"]));
+        assert!(require_name(TaxonomyKind::Category, &path(&[" "])).is_err());
+        assert!(require_name(TaxonomyKind::Tag, &path(&["a", "b"])).is_err());
+
+        let operation = Operation::Merge {
+            sources: vec![path(&["a/b"])],
+            target: path(&["c"]),
+        };
+        assert_eq!(apply_to_tags(&path(&["a/b", "c", "d"]), &operation), path(&["c", "d"]));
+    }
+
+    #[test]
+    fn category_with_slash_is_not_a_nested_path() {
+        let operation = Operation::Delete {
+            name: require_name(TaxonomyKind::Category, &path(&["A/B"])).unwrap(),
+        };
+        let paths = vec![path(&["A/B", "C"]), path(&["A", "B"])];
+        assert_eq!(apply_to_categories(&paths, &operation), vec![path(&["A", "B"])]);
+    }
+}
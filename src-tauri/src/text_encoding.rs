@@ -0,0 +1,197 @@
+// 文本编码与换行符处理
+// 旧版 Windows 博客中常见 GBK 编码的 markdown 和带 BOM 的 UTF-8 文件，
+// 读取时检测编码和换行符并统一为 UTF-8 + LF，写入时再按原格式还原
+
+use encoding_rs::{GB18030, UTF_16BE, UTF_16LE};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{self, CommandError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextEncoding {
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-8-bom")]
+    Utf8Bom,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    // GBK 是 GB18030 的子集，统一按 GB18030 处理
+    #[serde(rename = "gb18030")]
+    Gb18030,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+pub struct DecodedText {
+    // 已去除 BOM，换行符统一为 \n
+    pub content: String,
+    pub encoding: TextEncoding,
+    pub line_ending: LineEnding,
+}
+
+// 统计各种换行符，取出现最多的一种；没有换行时默认为 LF
+pub fn detect_line_ending(text: &str) -> LineEnding {
+    let bytes = text.as_bytes();
+    let (mut lf, mut crlf, mut cr) = (0usize, 0usize, 0usize);
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => {
+                crlf += 1;
+                i += 1;
+            }
+            b'\r' => cr += 1,
+            b'\n' => lf += 1,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if crlf > lf && crlf >= cr {
+        LineEnding::Crlf
+    } else if cr > lf && cr > crlf {
+        LineEnding::Cr
+    } else {
+        LineEnding::Lf
+    }
+}
+
+pub fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+fn apply_line_ending(text: &str, line_ending: LineEnding) -> String {
+    let normalized = normalize_line_endings(text);
+    match line_ending {
+        LineEnding::Lf => normalized,
+        LineEnding::Crlf => normalized.replace('\n', "\r\n"),
+        LineEnding::Cr => normalized.replace('\n', "\r"),
+    }
+}
+
+fn detect_encoding(bytes: &[u8]) -> TextEncoding {
+    if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        TextEncoding::Utf8Bom
+    } else if bytes.starts_with(&[0xFF, 0xFE]) {
+        TextEncoding::Utf16Le
+    } else if bytes.starts_with(&[0xFE, 0xFF]) {
+        TextEncoding::Utf16Be
+    } else if std::str::from_utf8(bytes).is_ok() {
+        TextEncoding::Utf8
+    } else {
+        TextEncoding::Gb18030
+    }
+}
+
+// 检测编码并解码为 UTF-8 字符串
+pub fn decode(bytes: &[u8]) -> Result<DecodedText, CommandError> {
+    let encoding = detect_encoding(bytes);
+    let text = match encoding {
+        TextEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+        TextEncoding::Utf8Bom => String::from_utf8_lossy(&bytes[3..]).into_owned(),
+        TextEncoding::Utf16Le => UTF_16LE.decode_with_bom_removal(bytes).0.into_owned(),
+        TextEncoding::Utf16Be => UTF_16BE.decode_with_bom_removal(bytes).0.into_owned(),
+        TextEncoding::Gb18030 => {
+            let (decoded, had_errors) = GB18030.decode_without_bom_handling(bytes);
+            if had_errors {
+                return Err(CommandError::new(
+                    error::UNSUPPORTED_ENCODING,
+                    "无法识别文件编码（既不是 UTF-8 也不是 GBK/GB18030）",
+                ));
+            }
+            decoded.into_owned()
+        }
+    };
+
+    Ok(DecodedText {
+        line_ending: detect_line_ending(&text),
+        content: normalize_line_endings(&text),
+        encoding,
+    })
+}
+
+// 按指定编码和换行符编码文本
+pub fn encode(content: &str, encoding: TextEncoding, line_ending: LineEnding) -> Vec<u8> {
+    let text = apply_line_ending(content, line_ending);
+    match encoding {
+        TextEncoding::Utf8 => text.into_bytes(),
+        TextEncoding::Utf8Bom => {
+            let mut bytes = vec![0xEF, 0xBB, 0xBF];
+            bytes.extend_from_slice(text.as_bytes());
+            bytes
+        }
+        // encoding_rs 不支持编码为 UTF-16，手动转换
+        TextEncoding::Utf16Le => {
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+            bytes
+        }
+        TextEncoding::Utf16Be => {
+            let mut bytes = vec![0xFE, 0xFF];
+            bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_be_bytes()));
+            bytes
+        }
+        TextEncoding::Gb18030 => GB18030.encode(&text).0.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(content: &str, encoding: TextEncoding, line_ending: LineEnding) {
+        let bytes = encode(content, encoding, line_ending);
+        let decoded = decode(&bytes).unwrap();
+        assert_eq!(decoded.encoding, encoding);
+        assert_eq!(decoded.line_ending, line_ending);
+        assert_eq!(decoded.content, content);
+        assert_eq!(encode(&decoded.content, decoded.encoding, decoded.line_ending), bytes);
+    }
+
+    #[test]
+    fn round_trips_every_encoding() {
+        let content = "---\ntitle: 你好 World\n---\n正文\n";
+        for encoding in [
+            TextEncoding::Utf8,
+            TextEncoding::Utf8Bom,
+            TextEncoding::Utf16Le,
+            TextEncoding::Utf16Be,
+            TextEncoding::Gb18030,
+        ] {
+            for line_ending in [LineEnding::Lf, LineEnding::Crlf, LineEnding::Cr] {
+                round_trip(content, encoding, line_ending);
+            }
+        }
+    }
+
+    #[test]
+    fn detects_gbk_and_the_dominant_line_ending() {
+        // "中文" 的 GBK 编码
+        let decoded = decode(&[0xD6, 0xD0, 0xCE, 0xC4, b'\r', b'\n', b'a', b'\r', b'\n', b'b', b'\n']).unwrap();
+        assert_eq!(decoded.encoding, TextEncoding::Gb18030);
+        assert_eq!(decoded.line_ending, LineEnding::Crlf);
+        assert_eq!(decoded.content, "中文\na\nb\n");
+    }
+
+    #[test]
+    fn plain_ascii_without_newlines_is_utf8_lf() {
+        let decoded = decode(b"hello").unwrap();
+        assert_eq!((decoded.encoding, decoded.line_ending), (TextEncoding::Utf8, LineEnding::Lf));
+    }
+
+    #[test]
+    fn rejects_undecodable_bytes() {
+        let Err(error) = decode(&[b'a', 0xFF, 0xFF]) else {
+            panic!("decode should fail");
+        };
+        assert_eq!(error.code, error::UNSUPPORTED_ENCODING);
+    }
+}
@@ -0,0 +1,185 @@
+// 递归遍历目录
+// list_files 只列出单层文件，source/_posts/2024/foo.md 或 new_post_name: :year/:title.md
+// 这类按目录组织的文章需要递归遍历才能找到。结果通过 Channel 分批推送给前端
+
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
+use tauri::State;
+use walkdir::WalkDir;
+
+use crate::error::{self, CommandError};
+use crate::scope::PathScope;
+use crate::{format_system_time, FileInfo};
+
+// 始终跳过的目录
+const SKIPPED_DIRECTORIES: [&str; 2] = ["node_modules", ".git"];
+// Hexo 生成目录，只在根目录下跳过（source 中可能有同名的普通目录）
+const GENERATED_DIRECTORY: &str = "public";
+// 每批推送的条目数
+const CHUNK_SIZE: usize = 200;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkOptions {
+    // 最大深度，根目录下的直接子项深度为 1；不设置则不限
+    pub max_depth: Option<usize>,
+    // 相对于根目录的 glob 模式（使用 / 分隔），为空时匹配所有文件
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub include_directories: bool,
+    #[serde(default)]
+    pub include_hidden: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WalkSummary {
+    pub total: usize,
+    pub chunks: usize,
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>, CommandError> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern)
+            .map_err(|e| CommandError::new(error::INVALID_PATH, format!("无效的匹配模式 {}: {}", pattern, e)))?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| CommandError::new(error::INVALID_PATH, e.to_string()))
+}
+
+// 遍历目录，每凑满一批就调用 on_chunk
+pub fn walk(
+    root: &Path,
+    display_root: &Path,
+    options: &WalkOptions,
+    mut on_chunk: impl FnMut(Vec<FileInfo>) -> Result<(), CommandError>,
+) -> Result<WalkSummary, CommandError> {
+    let include = build_glob_set(&options.include)?;
+    let exclude = build_glob_set(&options.exclude)?;
+
+    let mut walker = WalkDir::new(root).min_depth(1).follow_links(false);
+    if let Some(max_depth) = options.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+
+    let relative_of = |path: &Path| {
+        path.strip_prefix(root)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default()
+    };
+
+    let entries = walker.into_iter().filter_entry(|entry| {
+        let name = entry.file_name().to_string_lossy();
+        if !options.include_hidden && name.starts_with('.') {
+            return false;
+        }
+        if entry.file_type().is_dir() {
+            if SKIPPED_DIRECTORIES.contains(&name.as_ref()) {
+                return false;
+            }
+            if entry.depth() == 1 && name == GENERATED_DIRECTORY {
+                return false;
+            }
+            // 被排除的目录整体跳过，不再深入
+            if let Some(exclude) = &exclude {
+                if exclude.is_match(relative_of(entry.path())) {
+                    return false;
+                }
+            }
+        }
+        true
+    });
+
+    let mut summary = WalkSummary { total: 0, chunks: 0 };
+    let mut chunk = Vec::with_capacity(CHUNK_SIZE);
+
+    for entry in entries {
+        // 单个条目无法读取（权限等）时跳过，不中断整个遍历
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let is_directory = entry.file_type().is_dir();
+        if is_directory && !options.include_directories {
+            continue;
+        }
+        if !is_directory && !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = relative_of(entry.path());
+        if let Some(exclude) = &exclude {
+            if exclude.is_match(&relative) {
+                continue;
+            }
+        }
+        if let Some(include) = &include {
+            if !include.is_match(&relative) {
+                continue;
+            }
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let modified_time = metadata
+            .modified()
+            .map(format_system_time)
+            .unwrap_or_else(|_| "0".to_string());
+        let path = entry.path().strip_prefix(root).map(|p| display_root.join(p)).unwrap_or_else(|_| entry.path().to_path_buf());
+
+        chunk.push(FileInfo {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
+            is_directory,
+            size: if is_directory { 0 } else { metadata.len() },
+            modified_time,
+        });
+        summary.total += 1;
+
+        if chunk.len() >= CHUNK_SIZE {
+            on_chunk(std::mem::replace(&mut chunk, Vec::with_capacity(CHUNK_SIZE)))?;
+            summary.chunks += 1;
+        }
+    }
+
+    if !chunk.is_empty() {
+        on_chunk(chunk)?;
+        summary.chunks += 1;
+    }
+    Ok(summary)
+}
+
+// 递归列出目录下的文件，结果分批通过 on_chunk 推送，返回总数
+#[tauri::command]
+pub async fn walk_files(
+    directory_path: String,
+    options: Option<WalkOptions>,
+    on_chunk: Channel<Vec<FileInfo>>,
+    scope: State<'_, PathScope>,
+) -> Result<WalkSummary, CommandError> {
+    let root = scope.resolve(&directory_path)?;
+    let options = options.unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        walk(&root, Path::new(&directory_path), &options, |chunk| {
+            on_chunk
+                .send(chunk)
+                .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))
+        })
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
@@ -0,0 +1,299 @@
+// 项目文件监听
+// 监听 source/、scaffolds/、themes/*/_config.yml 和 _config*.yml，
+// 将 git、VS Code、Obsidian 等外部修改去抖后通过 "project-fs-change" 事件通知前端
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify_debouncer_full::notify::event::{ModifyKind, RenameMode};
+use notify_debouncer_full::notify::{EventKind, RecommendedWatcher, RecursiveMode};
+use notify_debouncer_full::{new_debouncer, DebounceEventResult, Debouncer, RecommendedCache};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::{self, CommandError};
+use crate::scope::PathScope;
+
+pub const FS_CHANGE_EVENT: &str = "project-fs-change";
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(300);
+
+// 按项目根目录保存正在运行的监听器，drop 即停止监听
+pub struct ProjectWatchers(Mutex<HashMap<PathBuf, Debouncer<RecommendedWatcher, RecommendedCache>>>);
+
+impl ProjectWatchers {
+    pub fn new() -> Self {
+        ProjectWatchers(Mutex::new(HashMap::new()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsChange {
+    // "create" | "modify" | "delete" | "rename"
+    pub kind: String,
+    pub path: String,
+    // 仅 rename 事件：重命名前的路径
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsChangeBatch {
+    pub project_path: String,
+    pub changes: Vec<FsChange>,
+}
+
+// 只关心项目内容相关的文件
+fn is_watched(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return false;
+    };
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+
+    match parts.as_slice() {
+        [first, ..] if first == "source" || first == "scaffolds" => {
+            // 隐藏文件（原子写入的临时文件、.bak 备份）和编辑器的临时文件
+            let name = parts.last().map(String::as_str).unwrap_or("");
+            !(name.starts_with('.') || name.ends_with(".tmp") || name.ends_with('~') || name.ends_with(".swp"))
+        }
+        [themes, _, file] => themes == "themes" && file == "_config.yml",
+        [file] => file.starts_with("_config") && (file.ends_with(".yml") || file.ends_with(".yaml")),
+        _ => false,
+    }
+}
+
+fn to_changes(root: &Path, result: DebounceEventResult) -> Vec<FsChange> {
+    let Ok(events) = result else {
+        return Vec::new();
+    };
+    let display = |path: &Path| path.to_string_lossy().to_string();
+
+    let mut changes: Vec<FsChange> = Vec::new();
+    for event in events {
+        let paths = &event.paths;
+        let change = match event.kind {
+            EventKind::Create(_) => paths.first().map(|p| ("create", p, None)),
+            EventKind::Remove(_) => paths.first().map(|p| ("delete", p, None)),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+                Some(("rename", &paths[1], Some(&paths[0])))
+            }
+            // 只收到单边重命名事件时，根据路径是否存在判断是移入还是移出
+            EventKind::Modify(ModifyKind::Name(_)) => paths.first().map(|p| {
+                if p.exists() {
+                    ("create", p, None)
+                } else {
+                    ("delete", p, None)
+                }
+            }),
+            EventKind::Modify(_) => paths.first().map(|p| ("modify", p, None)),
+            _ => None,
+        };
+
+        let Some((mut kind, mut path, mut from)) = change else {
+            continue;
+        };
+        if let Some(from_path) = from {
+            match (is_watched(root, from_path), is_watched(root, path)) {
+                (true, true) => {}
+                // 原子写入：临时文件重命名覆盖目标文件，对前端来说是一次修改
+                (false, true) => {
+                    kind = "modify";
+                    from = None;
+                }
+                (true, false) => {
+                    kind = "delete";
+                    path = from_path;
+                    from = None;
+                }
+                (false, false) => continue,
+            }
+        } else if !is_watched(root, path) {
+            continue;
+        }
+
+        let change = FsChange {
+            kind: kind.to_string(),
+            path: display(path),
+            from: from.map(|p| display(p)),
+        };
+        // 同一批次中同一路径的重复 modify 只保留一个
+        if change.kind == "modify" && changes.iter().any(|c| c.kind == "modify" && c.path == change.path) {
+            continue;
+        }
+        changes.push(change);
+    }
+    changes
+}
+
+// 开始监听项目（同一项目重复调用会重建监听器）
+#[tauri::command]
+pub async fn watch_project(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    watchers: State<'_, ProjectWatchers>,
+    app_handle: AppHandle,
+) -> Result<bool, CommandError> {
+    let root = scope.resolve(&project_path)?;
+    let watch_error = |e: notify_debouncer_full::notify::Error| CommandError::new(error::IO_ERROR, format!("文件监听失败: {}", e));
+
+    let handler_root = root.clone();
+    let handler_project_path = project_path.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE_TIMEOUT, None, move |result: DebounceEventResult| {
+        let changes = to_changes(&handler_root, result);
+        if changes.is_empty() {
+            return;
+        }
+        let _ = app_handle.emit(
+            FS_CHANGE_EVENT,
+            FsChangeBatch {
+                project_path: handler_project_path.clone(),
+                changes,
+            },
+        );
+    })
+    .map_err(watch_error)?;
+
+    // 根目录只监听本层（_config*.yml），内容目录递归监听
+    debouncer.watch(&root, RecursiveMode::NonRecursive).map_err(watch_error)?;
+    for dir in ["source", "scaffolds"] {
+        let path = root.join(dir);
+        if path.is_dir() {
+            debouncer.watch(&path, RecursiveMode::Recursive).map_err(watch_error)?;
+        }
+    }
+    if let Ok(themes) = fs::read_dir(root.join("themes")) {
+        for theme in themes.flatten() {
+            if theme.path().is_dir() {
+                debouncer.watch(theme.path(), RecursiveMode::NonRecursive).map_err(watch_error)?;
+            }
+        }
+    }
+
+    watchers.0.lock().unwrap().insert(root, debouncer);
+    Ok(true)
+}
+
+// 停止监听项目
+#[tauri::command]
+pub async fn unwatch_project(
+    project_path: String,
+    scope: State<'_, PathScope>,
+    watchers: State<'_, ProjectWatchers>,
+) -> Result<bool, CommandError> {
+    let root = scope.resolve(&project_path)?;
+    let removed = watchers.0.lock().unwrap().remove(&root);
+    Ok(removed.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::event::{CreateKind, DataChange, Event, RemoveKind};
+    use notify_debouncer_full::DebouncedEvent;
+    use std::time::Instant;
+
+    fn event(kind: EventKind, paths: &[&Path]) -> DebouncedEvent {
+        let event = paths
+            .iter()
+            .fold(Event::new(kind), |event, path| event.add_path(path.to_path_buf()));
+        DebouncedEvent::new(event, Instant::now())
+    }
+
+    fn summary(changes: &[FsChange]) -> Vec<(&str, &str, Option<&str>)> {
+        changes
+            .iter()
+            .map(|change| (change.kind.as_str(), change.path.as_str(), change.from.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn watches_only_content_files() {
+        let root = Path::new("/blog");
+        for path in [
+            "source/_posts/a.md",
+            "source/images/a.png",
+            "scaffolds/post.md",
+            "_config.yml",
+            "_config.butterfly.yaml",
+            "themes/next/_config.yml",
+        ] {
+            assert!(is_watched(root, &root.join(path)), "{} should be watched", path);
+        }
+        for path in [
+            "public/index.html",
+            "node_modules/hexo/package.json",
+            ".git/index",
+            "db.json",
+            "themes/next/layout/index.njk",
+            "source/_posts/.a.md.bak",
+            "source/_posts/.a.md.bak.1",
+            "source/_posts/.a.md.123-1-0.tmp",
+            "source/_posts/a.md.tmp",
+            "source/_posts/a.md~",
+            "source/_posts/a.md.swp",
+        ] {
+            assert!(!is_watched(root, &root.join(path)), "{} should be ignored", path);
+        }
+        assert!(!is_watched(root, Path::new("/other/source/a.md")));
+    }
+
+    #[test]
+    fn maps_rename_pairs() {
+        let root = Path::new("/blog");
+        let post = root.join("source/_posts/a.md");
+        let renamed = root.join("source/_posts/b.md");
+        let temp = root.join("source/_posts/.a.md.1-2-3.tmp");
+        let public = root.join("public/a.html");
+        let rename = EventKind::Modify(ModifyKind::Name(RenameMode::Both));
+
+        let changes = to_changes(
+            root,
+            Ok(vec![
+                event(rename, &[&post, &renamed]),
+                // 原子写入：临时文件覆盖目标文件
+                event(rename, &[&temp, &post]),
+                // 移出监听范围
+                event(rename, &[&renamed, &public]),
+                event(rename, &[&temp, &public]),
+            ]),
+        );
+        let post = post.to_string_lossy();
+        let renamed = renamed.to_string_lossy();
+        assert_eq!(
+            summary(&changes),
+            [
+                ("rename", renamed.as_ref(), Some(post.as_ref())),
+                ("modify", post.as_ref(), None),
+                ("delete", renamed.as_ref(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_ignored_paths_and_duplicate_modifies() {
+        let root = Path::new("/blog");
+        let post = root.join("source/_posts/a.md");
+        let backup = root.join("source/_posts/.a.md.bak");
+        let modify = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+
+        let changes = to_changes(
+            root,
+            Ok(vec![
+                event(EventKind::Create(CreateKind::File), &[&backup]),
+                event(EventKind::Create(CreateKind::File), &[&root.join("node_modules/x.js")]),
+                event(modify, &[&post]),
+                event(modify, &[&post]),
+                event(EventKind::Remove(RemoveKind::File), &[&post]),
+            ]),
+        );
+        let post = post.to_string_lossy();
+        assert_eq!(summary(&changes), [("modify", post.as_ref(), None), ("delete", post.as_ref(), None)]);
+        assert!(to_changes(root, Err(Vec::new())).is_empty());
+    }
+}
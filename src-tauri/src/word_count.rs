@@ -0,0 +1,355 @@
+// 字数统计
+// 中日韩文字按字计数，其他文字按单词计数，两者分开统计。
+// 统计前去掉代码块、行内代码、HTML 注释和标签、Hexo 标签（codeblock 等代码类标签连同内容一起去掉）、
+// 图片和链接地址，只统计读者看到的正文；阅读时间按中文每分钟 300 字、英文每分钟 200 词估算
+
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use rayon::prelude::*;
+use regex::Regex;
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::{self, CommandError};
+use crate::front_matter::parse_front_matter;
+use crate::post_index::{post_files, scalar_to_string};
+use crate::scope::PathScope;
+use crate::text_encoding;
+
+const CJK_CHARS_PER_MINUTE: f64 = 300.0;
+const LATIN_WORDS_PER_MINUTE: f64 = 200.0;
+// 内容需要整体去掉的 Hexo 标签
+const CODE_TAGS: [&str; 3] = ["codeblock", "code", "raw"];
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub cjk_characters: usize,
+    pub latin_words: usize,
+    // cjk_characters + latin_words
+    pub words: usize,
+    // 估算的阅读时间（分钟，向上取整；有内容时至少 1 分钟）
+    pub reading_minutes: usize,
+}
+
+impl TextStats {
+    fn new(cjk_characters: usize, latin_words: usize) -> Self {
+        let minutes = cjk_characters as f64 / CJK_CHARS_PER_MINUTE + latin_words as f64 / LATIN_WORDS_PER_MINUTE;
+        TextStats {
+            cjk_characters,
+            latin_words,
+            words: cjk_characters + latin_words,
+            reading_minutes: minutes.ceil() as usize,
+        }
+    }
+
+    fn add(self, other: TextStats) -> TextStats {
+        TextStats::new(self.cjk_characters + other.cjk_characters, self.latin_words + other.latin_words)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostTextStats {
+    pub path: String,
+    pub relative_path: String,
+    pub title: Option<String>,
+    pub draft: bool,
+    pub stats: TextStats,
+    // 读取或解析失败时的错误信息
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTextStats {
+    // 已发布文章（source/_posts）的合计
+    pub published: TextStats,
+    // 草稿（source/_drafts）的合计
+    pub drafts: TextStats,
+    pub total: TextStats,
+    pub posts: Vec<PostTextStats>,
+}
+
+// 中日韩统一表意文字、假名和谚文
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF      // 平假名、片假名
+        | 0x3400..=0x4DBF    // 扩展 A
+        | 0x4E00..=0x9FFF    // 基本区
+        | 0xAC00..=0xD7AF    // 谚文音节
+        | 0xF900..=0xFAFF    // 兼容表意文字
+        | 0x20000..=0x2EBEF  // 扩展 B - F
+    )
+}
+
+struct Patterns {
+    code_tags: Vec<Regex>,
+    comment: Regex,
+    hexo_tag: Regex,
+    template_variable: Regex,
+    inline_code: Regex,
+    image: Regex,
+    link: Regex,
+    url: Regex,
+    html_tag: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        code_tags: CODE_TAGS
+            .iter()
+            .map(|tag| Regex::new(&format!(r"(?s)\{{%\s*{tag}\b.*?%\}}.*?\{{%\s*end{tag}\s*%\}}")).unwrap())
+            .collect(),
+        comment: Regex::new(r"(?s)<!--.*?-->").unwrap(),
+        hexo_tag: Regex::new(r"(?s)\{%.*?%\}").unwrap(),
+        template_variable: Regex::new(r"(?s)\{\{.*?\}\}").unwrap(),
+        inline_code: Regex::new(r"`[^`\n]*`").unwrap(),
+        image: Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap(),
+        link: Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap(),
+        url: Regex::new(r"https?://\S+").unwrap(),
+        html_tag: Regex::new(r"<[^>\n]+>").unwrap(),
+    })
+}
+
+enum OpenBlock {
+    // 开始围栏（``` 或 ~~~，可以更长）
+    Fence(String),
+    // 代码类 Hexo 标签名（codeblock 或 code）
+    Tag(String),
+}
+
+// 逐行判断是否处于代码块中：``` / ~~~ 围栏（结束围栏用同一字符、不短于开始围栏且不带信息字符串）
+// 以及 {% codeblock %}…{% endcodeblock %}、{% code %}…{% endcode %} 标签。字数统计和链接检查共用
+#[derive(Default)]
+pub(crate) struct CodeBlocks {
+    open: Option<OpenBlock>,
+}
+
+fn fence_marker(line: &str) -> &str {
+    let Some(first) = line.chars().next().filter(|c| *c == '`' || *c == '~') else {
+        return "";
+    };
+    &line[..line.find(|c| c != first).unwrap_or(line.len())]
+}
+
+fn code_tag() -> &'static (Regex, Regex) {
+    static PATTERNS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        (
+            Regex::new(r"^\{%\s*(codeblock|code)\b.*?%\}").unwrap(),
+            Regex::new(r"\{%\s*end(codeblock|code)\s*%\}").unwrap(),
+        )
+    })
+}
+
+impl CodeBlocks {
+    // 该行属于代码块（包括开始和结束行）时返回 true
+    pub(crate) fn is_code(&mut self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        let (open_tag, end_tag) = code_tag();
+        match &self.open {
+            Some(OpenBlock::Fence(open)) => {
+                let marker = fence_marker(trimmed);
+                if marker.starts_with(&open[..1]) && marker.len() >= open.len() && trimmed[marker.len()..].trim().is_empty() {
+                    self.open = None;
+                }
+                true
+            }
+            Some(OpenBlock::Tag(tag)) => {
+                if end_tag.captures(trimmed).is_some_and(|caps| &caps[1] == tag) {
+                    self.open = None;
+                }
+                true
+            }
+            None => {
+                let marker = fence_marker(trimmed);
+                // ``` 围栏的信息字符串中不能有反引号（否则是行内代码）
+                if marker.len() >= 3 && !(marker.starts_with('`') && trimmed[marker.len()..].contains('`')) {
+                    self.open = Some(OpenBlock::Fence(marker.to_string()));
+                    return true;
+                }
+                let Some(caps) = open_tag.captures(trimmed) else {
+                    return false;
+                };
+                let tag = &caps[1];
+                // 开始和结束标签在同一行时代码块只有这一行
+                let closed = end_tag
+                    .captures(&trimmed[caps.get(0).unwrap().end()..])
+                    .is_some_and(|end| &end[1] == tag);
+                if !closed {
+                    self.open = Some(OpenBlock::Tag(tag.to_string()));
+                }
+                true
+            }
+        }
+    }
+}
+
+// 去掉代码块
+fn strip_code_blocks(text: &str) -> String {
+    let mut blocks = CodeBlocks::default();
+    let mut result = String::with_capacity(text.len());
+    for line in text.lines().filter(|line| !blocks.is_code(line)) {
+        result.push_str(line);
+        result.push('\n');
+    }
+    result
+}
+
+// 只保留读者看到的正文
+fn visible_text(markdown: &str) -> String {
+    let patterns = patterns();
+    let mut text = strip_code_blocks(markdown);
+    for tag in &patterns.code_tags {
+        text = tag.replace_all(&text, " ").to_string();
+    }
+    for pattern in [&patterns.comment, &patterns.hexo_tag, &patterns.template_variable, &patterns.inline_code, &patterns.image] {
+        text = pattern.replace_all(&text, " ").to_string();
+    }
+    text = patterns.link.replace_all(&text, " $1 ").to_string();
+    for pattern in [&patterns.url, &patterns.html_tag] {
+        text = pattern.replace_all(&text, " ").to_string();
+    }
+    text
+}
+
+fn count(text: &str) -> (usize, usize) {
+    let mut cjk = 0;
+    let mut latin = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                latin += 1;
+                in_word = true;
+            }
+        } else if !(in_word && matches!(c, '\'' | '’' | '-' | '_')) {
+            // don't、well-known 这类单词中间的符号不拆分
+            in_word = false;
+        }
+    }
+    (cjk, latin)
+}
+
+// 统计 markdown 正文（不含 front-matter）
+pub fn text_stats(markdown: &str) -> TextStats {
+    let (cjk, latin) = count(&visible_text(markdown));
+    TextStats::new(cjk, latin)
+}
+
+fn post_text_stats_of(project_root: &Path, path: &Path) -> PostTextStats {
+    let relative = path.strip_prefix(project_root).unwrap_or(path);
+    let mut result = PostTextStats {
+        path: path.to_string_lossy().to_string(),
+        relative_path: relative.to_string_lossy().replace('\\', "/"),
+        title: None,
+        draft: relative.starts_with(Path::new("source").join("_drafts")),
+        stats: TextStats::default(),
+        error: None,
+    };
+    let parsed = fs::read(path)
+        .map_err(CommandError::from)
+        .and_then(|bytes| text_encoding::decode(&bytes))
+        .and_then(|decoded| parse_front_matter(&decoded.content));
+    match parsed {
+        Ok(front_matter) => {
+            result.title = front_matter.data.get("title").and_then(scalar_to_string);
+            result.stats = text_stats(&front_matter.body);
+        }
+        Err(e) => result.error = Some(e.message),
+    }
+    result
+}
+
+// 单篇文章的字数和阅读时间
+#[tauri::command]
+pub async fn post_text_stats(file_path: String, scope: State<'_, PathScope>) -> Result<TextStats, CommandError> {
+    let path = scope.resolve(&file_path)?;
+    let decoded = text_encoding::decode(&fs::read(&path)?)?;
+    let front_matter = parse_front_matter(&decoded.content)?;
+    Ok(text_stats(&front_matter.body))
+}
+
+// 项目中所有文章的字数统计（按字数从多到少）
+#[tauri::command]
+pub async fn project_text_stats(project_path: String, scope: State<'_, PathScope>) -> Result<ProjectTextStats, CommandError> {
+    let project_root = scope.resolve(&project_path)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut posts: Vec<PostTextStats> = post_files(&project_root)
+            .par_iter()
+            .map(|path| post_text_stats_of(&project_root, path))
+            .collect();
+        posts.sort_by(|a, b| b.stats.words.cmp(&a.stats.words).then_with(|| a.relative_path.cmp(&b.relative_path)));
+
+        let sum = |draft: bool| {
+            posts
+                .iter()
+                .filter(|post| post.draft == draft)
+                .fold(TextStats::default(), |total, post| total.add(post.stats))
+        };
+        let published = sum(false);
+        let drafts = sum(true);
+        Ok(ProjectTextStats {
+            published,
+            drafts,
+            total: published.add(drafts),
+            posts,
+        })
+    })
+    .await
+    .map_err(|e| CommandError::new(error::IO_ERROR, e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_cjk_characters_and_latin_words_separately() {
+        let stats = text_stats("你好，世界！Hello well-known world, don't panic.");
+        assert_eq!((stats.cjk_characters, stats.latin_words, stats.words), (4, 5, 9));
+        assert_eq!(stats.reading_minutes, 1);
+    }
+
+    #[test]
+    fn ignores_code_markup_and_urls() {
+        let markdown = "正文 [链接](https://example.com/a) ![图](a.png)\n\
+                        `inline code` <!-- 注释 --> <span>标签</span> https://example.com\n\
+                        ```rust\nfn main() {}\n```\n\
+                        {% codeblock %}\nlet x = 1;\n{% endcodeblock %}\n\
+                        {% post_link hello %} {{ page.title }}\n";
+        let stats = text_stats(markdown);
+        assert_eq!((stats.cjk_characters, stats.latin_words), (6, 0));
+    }
+
+    #[test]
+    fn tracks_fences_and_code_tags() {
+        let text = "a\n\
+                    ````md\n```js\nb\n```\n````\n\
+                    c\n\
+                    ~~~ ```\nd\n~~~\n\
+                    ``` `e` ```\n\
+                    {% codeblock lang:js %}\nf\n{% endcode %}\n```\n{% endcodeblock %}\n\
+                    {% code %}g{% endcode %}\n\
+                    h\n\
+                    ```\ni\n``` not closed\n";
+        let mut blocks = CodeBlocks::default();
+        let visible: Vec<&str> = text.lines().filter(|line| !blocks.is_code(line)).collect();
+        assert_eq!(visible, ["a", "c", "``` `e` ```", "h"]);
+    }
+
+    #[test]
+    fn empty_text_takes_no_time() {
+        let stats = text_stats("```\ncode only\n```\n");
+        assert_eq!((stats.words, stats.reading_minutes), (0, 0));
+        assert_eq!(text_stats(&"字".repeat(301)).reading_minutes, 2);
+    }
+}
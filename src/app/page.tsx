@@ -257,8 +257,10 @@ export default function Home() {
               
               if (isTauriEnv) {
                 // Tauri 环境使用 convertFileSrc（推荐方式，无需 base64 编码）
-                const { convertFileSrc } = await import('@tauri-apps/api/core');
-                const assetUrl = convertFileSrc(backgroundImage);
+                const { convertFileSrc, invoke } = await import('@tauri-apps/api/core');
+                // asset 协议只允许访问已打开的项目目录和应用数据目录，背景图片先复制到应用数据目录
+                const importedPath = await invoke<string>('import_background_image', { path: backgroundImage });
+                const assetUrl = convertFileSrc(importedPath);
                 document.documentElement.style.setProperty('--bg-image', `url(${assetUrl})`);
                 console.log('设置背景图片 (Tauri asset URL):', assetUrl);
               } else {
@@ -1044,7 +1046,10 @@ export default function Home() {
     setIsLoading(true);
     try {
       const ipcRenderer = await getIpcRenderer();
-      const files = await ipcRenderer.invoke('list-files', path + '/source/_posts');
+      // Tauri 递归遍历 _posts，按年份等子目录组织的文章也能列出；Electron 仍只列出单层
+      const files = isTauri()
+        ? await ipcRenderer.invoke('walk-files', path + '/source/_posts', { include: ['**/*.md', '**/*.markdown'] })
+        : await ipcRenderer.invoke('list-files', path + '/source/_posts');
 
       const markdownFiles = files
         .filter((file: any) =>
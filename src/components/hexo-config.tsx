@@ -138,7 +138,8 @@ export function HexoConfig({ hexoPath, onConfigUpdate }: HexoConfigProps) {
     try {
       const ipcRenderer = await getIpcRenderer();
       const configPath = `${hexoPath}/_config.yml`;
-      await ipcRenderer.invoke('write-file', configPath, rawConfig);
+      // 保存配置时保留 .bak 备份（轮换保留最近 3 份）
+      await ipcRenderer.invoke('write-file', configPath, rawConfig, { backup: true });
 
       setSaveResult({
         success: true,
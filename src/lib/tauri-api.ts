@@ -10,6 +10,46 @@ function isTauriEnvironment(): boolean {
          !!(window as any).ipc;
 }
 
+// 调用文件类命令：Rust 端返回 { code, message } 结构化错误，转换为带 code 的 Error
+async function invokeFileCommand<T>(
+  command: string,
+  args?: Record<string, unknown> | Uint8Array,
+  options?: { headers: Record<string, string> },
+): Promise<T> {
+  const { invoke } = await import('@tauri-apps/api/core');
+  try {
+    return await invoke<T>(command, args as any, options);
+  } catch (error: any) {
+    if (error && typeof error === 'object' && 'code' in error) {
+      const wrapped = new Error(error.message) as Error & { code?: string; details?: unknown };
+      wrapped.code = error.code;
+      wrapped.details = error.details;
+      throw wrapped;
+    }
+    throw error;
+  }
+}
+
+export type TextEncoding = 'utf-8' | 'utf-8-bom' | 'utf-16le' | 'utf-16be' | 'gb18030';
+export type LineEnding = 'lf' | 'crlf' | 'cr';
+
+export interface WriteFileOptions {
+  backup?: boolean;
+  expectedHash?: string;
+  encoding?: TextEncoding;
+  lineEnding?: LineEnding;
+}
+
+export interface WalkOptions {
+  // 最大深度，直接子项为 1
+  maxDepth?: number;
+  // 相对于根目录的 glob 模式
+  include?: string[];
+  exclude?: string[];
+  includeDirectories?: boolean;
+  includeHidden?: boolean;
+}
+
 // 窗口控制
 export const windowControls = {
   minimize: async () => {
@@ -85,9 +125,21 @@ export const fileOperations = {
   },
   
   readFile: async (filePath: string): Promise<string> => {
+    const file = await fileOperations.readFileWithMeta(filePath);
+    return file.content;
+  },
+  
+  // 读取文件并返回内容哈希和修改时间，保存时可作为 expectedHash 传回以检测外部修改
+  // 内容已统一为 UTF-8 + \n，encoding / lineEnding 为文件原有格式
+  readFileWithMeta: async (filePath: string): Promise<{
+    content: string;
+    hash: string;
+    modifiedTime: string;
+    encoding: TextEncoding;
+    lineEnding: LineEnding;
+  }> => {
     if (isTauriEnvironment()) {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke('read_file', { filePath });
+      return await invokeFileCommand('read_file', { filePath });
     }
     throw new Error('Not in Tauri environment');
   },
@@ -103,34 +155,99 @@ export const fileOperations = {
     throw new Error('Not in Tauri environment');
   },
   
-  writeFile: async (filePath: string, content: string): Promise<boolean> => {
+  // expectedHash 与磁盘内容不一致时抛出 code 为 CONFLICT 的错误，details 中附带当前磁盘内容
+  // 默认沿用文件原有的编码和换行符，传入 encoding / lineEnding 时转换
+  writeFile: async (filePath: string, content: string, options?: WriteFileOptions): Promise<boolean> => {
     if (isTauriEnvironment()) {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke('write_file', { filePath, content });
+      return await invokeFileCommand<boolean>('write_file', {
+        filePath,
+        content,
+        backup: options?.backup,
+        expectedHash: options?.expectedHash,
+        encoding: options?.encoding,
+        lineEnding: options?.lineEnding,
+      });
     }
     throw new Error('Not in Tauri environment');
   },
   
   copyFile: async (sourcePath: string, destinationPath: string): Promise<string> => {
     if (isTauriEnvironment()) {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke('copy_file', { sourcePath, destinationPath });
+      return await invokeFileCommand<string>('copy_file', { sourcePath, destinationPath });
+    }
+    throw new Error('Not in Tauri environment');
+  },
+  
+  readFileBytes: async (filePath: string): Promise<Uint8Array> => {
+    if (isTauriEnvironment()) {
+      const buffer = await invokeFileCommand<ArrayBuffer>('read_file_bytes', { filePath });
+      return new Uint8Array(buffer);
+    }
+    throw new Error('Not in Tauri environment');
+  },
+  
+  // 以原始字节写入文件，路径通过请求头传递（编码后支持中文路径）
+  writeFileBytes: async (filePath: string, data: Uint8Array): Promise<string> => {
+    if (isTauriEnvironment()) {
+      return await invokeFileCommand<string>('write_file_bytes', data as any, {
+        headers: { 'x-file-path': encodeURIComponent(filePath) },
+      });
+    }
+    throw new Error('Not in Tauri environment');
+  },
+  
+  ensureDir: async (directoryPath: string): Promise<boolean> => {
+    if (isTauriEnvironment()) {
+      return await invokeFileCommand<boolean>('ensure_dir', { directoryPath });
     }
     throw new Error('Not in Tauri environment');
   },
   
   deleteFile: async (filePath: string): Promise<boolean> => {
     if (isTauriEnvironment()) {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke('delete_file', { filePath });
+      return await invokeFileCommand<boolean>('delete_file', { filePath });
     }
     throw new Error('Not in Tauri environment');
   },
   
   listFiles: async (directoryPath: string): Promise<any[]> => {
     if (isTauriEnvironment()) {
-      const { invoke } = await import('@tauri-apps/api/core');
-      return await invoke('list_files', { directoryPath });
+      return await invokeFileCommand<any[]>('list_files', { directoryPath });
+    }
+    throw new Error('Not in Tauri environment');
+  },
+  
+  // 递归列出目录下的文件，结果分批推送（每批调用一次 onChunk），返回全部结果
+  walkFiles: async (
+    directoryPath: string,
+    options?: WalkOptions,
+    onChunk?: (chunk: any[]) => void,
+  ): Promise<any[]> => {
+    if (isTauriEnvironment()) {
+      const { Channel } = await import('@tauri-apps/api/core');
+      const files: any[] = [];
+      let total = Infinity;
+      let finish: () => void = () => {};
+      const received = new Promise<void>((resolve) => { finish = resolve; });
+      
+      const channel = new Channel<any[]>();
+      channel.onmessage = (chunk) => {
+        files.push(...chunk);
+        onChunk?.(chunk);
+        if (files.length >= total) finish();
+      };
+      
+      const summary = await invokeFileCommand<{ total: number; chunks: number }>('walk_files', {
+        directoryPath,
+        options,
+        onChunk: channel,
+      });
+      // 命令返回时最后几批可能还没有送达
+      total = summary.total;
+      if (files.length < total) {
+        await received;
+      }
+      return files;
     }
     throw new Error('Not in Tauri environment');
   },
@@ -252,13 +369,19 @@ export const ipcRenderer = {
       case 'convert-file-src':
         return fileOperations.convertFileSrc(args[0]);
       case 'write-file':
-        return fileOperations.writeFile(args[0], args[1]);
+        return fileOperations.writeFile(args[0], args[1], args[2]);
       case 'copy-file':
         return fileOperations.copyFile(args[0], args[1]);
+      case 'write-file-from-buffer':
+        return fileOperations.writeFileBytes(args[0], args[1]);
+      case 'ensure-directory-exists':
+        return fileOperations.ensureDir(args[0]);
       case 'delete-file':
         return fileOperations.deleteFile(args[0]);
       case 'list-files':
         return fileOperations.listFiles(args[0]);
+      case 'walk-files':
+        return fileOperations.walkFiles(args[0], args[1]);
       case 'execute-command':
         return commandOperations.execute(args[0]);
       case 'execute-hexo-command':